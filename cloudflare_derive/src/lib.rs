@@ -23,14 +23,14 @@ pub fn dns_common(input: TokenStream) -> TokenStream {
     let struct_name = s.pop().unwrap();
     let impl_open = format!("impl DnsCommon for {}", struct_name);
     let impl_body = "{
-    fn get_id(self) -> Option<String>{
+    fn get_id(self) -> Option<RecordId>{
         self.id
     }
-    fn set_id(&mut self, v: Option<String>){
+    fn set_id(&mut self, v: Option<RecordId>){
         self.id = v;
     }
-    fn get_zone_id(self) -> Option<String> {self.zone_id}
-    fn set_zone_id(&mut self, v: Option<String>) {
+    fn get_zone_id(self) -> Option<ZoneId> {self.zone_id}
+    fn set_zone_id(&mut self, v: Option<ZoneId>) {
         self.zone_id = v;
     }
     fn get_zone_name(self) -> Option<String> {self.zone_name}
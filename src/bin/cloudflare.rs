@@ -0,0 +1,425 @@
+use std::{
+    net::{Ipv4Addr, Ipv6Addr},
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use cloudflare_rs::{
+    AAAARecordRequest, ARecordRequest, ApiError, ApiErrorKind, ApiResult, CNAMERecordRequest,
+    Cloudflare, Credentials, MXRecordRequest, RecordId, RecordMessage, RecordRequest,
+    TXTRecordRequest, ZoneId,
+};
+use serde::Serialize;
+
+#[derive(Parser)]
+#[command(name = "cloudflare", about = "Manage Cloudflare zones and DNS records")]
+struct Cli {
+    /// API token. Overrides the environment and config file.
+    #[arg(long, global = true)]
+    token: Option<String>,
+    /// Config file with named profiles. Defaults to ~/.config/cloudflare/config.toml.
+    #[arg(long, env = "CLOUDFLARE_CONFIG", global = true)]
+    config: Option<PathBuf>,
+    /// Profile to read from the config file. Its credentials win over the environment.
+    #[arg(long, env = "CLOUDFLARE_PROFILE", global = true)]
+    profile: Option<String>,
+    #[arg(long, short, value_enum, default_value_t = Output::Table, global = true)]
+    output: Output,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Output {
+    Json,
+    Table,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage DNS records. ZONE is a zone id or any name inside the zone.
+    #[command(subcommand)]
+    Records(RecordsCommand),
+    /// Look up and export zones.
+    #[command(subcommand)]
+    Zones(ZonesCommand),
+    /// Check the configured API token.
+    #[command(subcommand)]
+    Token(TokenCommand),
+}
+
+#[derive(Subcommand)]
+enum RecordsCommand {
+    List {
+        zone: String,
+    },
+    Get {
+        zone: String,
+        id: RecordId,
+    },
+    Create {
+        zone: String,
+        #[command(flatten)]
+        record: RecordArgs,
+    },
+    Update {
+        zone: String,
+        id: RecordId,
+        #[command(flatten)]
+        record: RecordArgs,
+    },
+    Delete {
+        zone: String,
+        id: RecordId,
+    },
+}
+
+#[derive(Subcommand)]
+enum ZonesCommand {
+    /// Print the id of the zone that owns NAME.
+    Lookup { name: String },
+    /// Print the zone as a BIND zone file.
+    Export { zone: String },
+}
+
+#[derive(Subcommand)]
+enum TokenCommand {
+    Verify,
+}
+
+#[derive(clap::Args)]
+struct RecordArgs {
+    #[arg(long = "type")]
+    record_type: String,
+    #[arg(long)]
+    name: Option<String>,
+    #[arg(long)]
+    content: Option<String>,
+    #[arg(long)]
+    ttl: Option<usize>,
+    #[arg(long)]
+    proxied: Option<bool>,
+    #[arg(long)]
+    priority: Option<usize>,
+    #[arg(long)]
+    comment: Option<String>,
+}
+
+fn invalid(msg: impl ToString) -> ApiError {
+    ApiError::with_kind(ApiErrorKind::Invalid, msg)
+}
+
+/// Exit status for a failed command. 2 is left to clap, which uses it for usage errors.
+fn exit_code(kind: ApiErrorKind) -> u8 {
+    match kind {
+        ApiErrorKind::Api => 1,
+        ApiErrorKind::Invalid => 9,
+        ApiErrorKind::Auth => 3,
+        ApiErrorKind::NotFound => 4,
+        ApiErrorKind::RateLimited => 5,
+        ApiErrorKind::Transport => 6,
+        ApiErrorKind::Decode => 7,
+        ApiErrorKind::Timeout => 8,
+    }
+}
+
+fn client(cli: &Cli) -> ApiResult<Cloudflare> {
+    if let Some(token) = &cli.token {
+        return Ok(Cloudflare::default().with_token(token));
+    }
+    let creds = Credentials::load(cli.config.as_deref(), cli.profile.as_deref())?;
+    Ok(creds.client()?)
+}
+
+async fn zone_id(c: &Cloudflare, zone: &str) -> ApiResult<ZoneId> {
+    match ZoneId::from_str(zone) {
+        Ok(id) => Ok(id),
+        Err(_) => c.zones().resolve_zone(zone).await,
+    }
+}
+
+fn record_request(args: RecordArgs) -> ApiResult<RecordRequest> {
+    let RecordArgs {
+        record_type,
+        name,
+        content,
+        ttl,
+        proxied,
+        priority,
+        comment,
+    } = args;
+    let parse_err = |e: std::net::AddrParseError| invalid(format!("invalid content: {}", e));
+    let record = match record_type.to_ascii_uppercase().as_str() {
+        "A" => RecordRequest::A(ARecordRequest {
+            name,
+            content: content
+                .map(|c| Ipv4Addr::from_str(&c))
+                .transpose()
+                .map_err(parse_err)?,
+            ttl,
+            proxied,
+            comment,
+            ..Default::default()
+        }),
+        "AAAA" => RecordRequest::AAAA(AAAARecordRequest {
+            name,
+            content: content
+                .map(|c| Ipv6Addr::from_str(&c))
+                .transpose()
+                .map_err(parse_err)?,
+            ttl,
+            proxied,
+            comment,
+            ..Default::default()
+        }),
+        "CNAME" => RecordRequest::CNAME(CNAMERecordRequest {
+            name,
+            content,
+            ttl,
+            proxied,
+            comment,
+            ..Default::default()
+        }),
+        "MX" => RecordRequest::MX(MXRecordRequest {
+            name,
+            content,
+            ttl,
+            proxied,
+            priority,
+            comment,
+            ..Default::default()
+        }),
+        "TXT" => RecordRequest::TXT(TXTRecordRequest {
+            name,
+            content,
+            ttl,
+            proxied,
+            comment,
+            ..Default::default()
+        }),
+        t => return Err(invalid(format!("unsupported record type {}", t))),
+    };
+    Ok(record)
+}
+
+fn print_json(v: &impl Serialize) -> ApiResult<()> {
+    let s = serde_json::to_string_pretty(v).map_err(invalid)?;
+    println!("{}", s);
+    Ok(())
+}
+
+fn print_table(rows: Vec<Vec<String>>) {
+    let mut widths = vec![0; rows.first().map(|r| r.len()).unwrap_or_default()];
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+    for row in rows {
+        let line: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| format!("{:width$}", cell, width = widths[i]))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+fn print_records(output: Output, records: &[RecordMessage]) -> ApiResult<()> {
+    if let Output::Json = output {
+        return print_json(&records);
+    }
+    let mut rows = vec![vec![
+        "ID".to_string(),
+        "TYPE".to_string(),
+        "NAME".to_string(),
+        "CONTENT".to_string(),
+        "TTL".to_string(),
+        "PROXIED".to_string(),
+    ]];
+    for record in records {
+        let req = RecordRequest::from(record.clone());
+        let (ttl, proxied) = match &req {
+            RecordRequest::A(r) => (r.ttl, r.proxied),
+            RecordRequest::AAAA(r) => (r.ttl, r.proxied),
+            RecordRequest::CNAME(r) => (r.ttl, r.proxied),
+            RecordRequest::MX(r) => (r.ttl, r.proxied),
+            RecordRequest::TXT(r) => (r.ttl, r.proxied),
+            RecordRequest::Other(r) => (r.ttl, r.proxied),
+        };
+        rows.push(vec![
+            record.id().map(|i| i.to_string()).unwrap_or_default(),
+            req.record_type().to_string(),
+            req.name().unwrap_or_default().to_string(),
+            req.content().unwrap_or_default(),
+            match ttl {
+                Some(1) => "auto".to_string(),
+                Some(t) => t.to_string(),
+                None => String::new(),
+            },
+            proxied.map(|p| p.to_string()).unwrap_or_default(),
+        ]);
+    }
+    print_table(rows);
+    Ok(())
+}
+
+async fn run(cli: Cli) -> ApiResult<()> {
+    let c = client(&cli)?;
+    let dns = c.dns();
+    match cli.command {
+        Command::Records(cmd) => match cmd {
+            RecordsCommand::List { zone } => {
+                let zone_id = zone_id(&c, &zone).await?;
+                let records = dns.list_all_records(&zone_id).await?;
+                print_records(cli.output, &records)
+            }
+            RecordsCommand::Get { zone, id } => {
+                let zone_id = zone_id(&c, &zone).await?;
+                let record = dns.get_record(&zone_id, &id).await?.into_result()?;
+                print_records(cli.output, &[record])
+            }
+            RecordsCommand::Create { zone, record } => {
+                let zone_id = zone_id(&c, &zone).await?;
+                let record = dns
+                    .create_record(&zone_id, record_request(record)?)
+                    .await?
+                    .into_result()?;
+                print_records(cli.output, &[record])
+            }
+            RecordsCommand::Update { zone, id, record } => {
+                let zone_id = zone_id(&c, &zone).await?;
+                let record = dns
+                    .update_record(&zone_id, &id, record_request(record)?)
+                    .await?
+                    .into_result()?;
+                print_records(cli.output, &[record])
+            }
+            RecordsCommand::Delete { zone, id } => {
+                let zone_id = zone_id(&c, &zone).await?;
+                let deleted = dns.delete_record(&zone_id, &id).await?.into_result()?;
+                match cli.output {
+                    Output::Json => print_json(&deleted),
+                    Output::Table => {
+                        println!("deleted {}", deleted.id);
+                        Ok(())
+                    }
+                }
+            }
+        },
+        Command::Zones(cmd) => match cmd {
+            ZonesCommand::Lookup { name } => {
+                let id = c.zones().resolve_zone(&name).await?;
+                match cli.output {
+                    Output::Json => print_json(&serde_json::json!({ "name": name, "id": id })),
+                    Output::Table => {
+                        println!("{}", id);
+                        Ok(())
+                    }
+                }
+            }
+            ZonesCommand::Export { zone } => {
+                let zone_id = zone_id(&c, &zone).await?;
+                let zone_file = dns.export_records(&zone_id).await?;
+                match cli.output {
+                    Output::Json => print_json(&serde_json::json!({ "zone_file": zone_file })),
+                    Output::Table => {
+                        print!("{}", zone_file);
+                        Ok(())
+                    }
+                }
+            }
+        },
+        Command::Token(TokenCommand::Verify) => {
+            let status = c.verify_token().await?;
+            match cli.output {
+                Output::Json => print_json(&status.result),
+                Output::Table => {
+                    println!("{} {}", status.result.id, status.result.status);
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e.message());
+            ExitCode::from(exit_code(e.kind()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(record_type: &str, content: &str) -> RecordArgs {
+        RecordArgs {
+            record_type: record_type.into(),
+            name: Some("www.example.com".into()),
+            content: Some(content.into()),
+            ttl: None,
+            proxied: None,
+            priority: None,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn exit_codes_are_distinct_and_avoid_usage_errors() {
+        let kinds = [
+            ApiErrorKind::Api,
+            ApiErrorKind::Invalid,
+            ApiErrorKind::Auth,
+            ApiErrorKind::NotFound,
+            ApiErrorKind::RateLimited,
+            ApiErrorKind::Transport,
+            ApiErrorKind::Decode,
+            ApiErrorKind::Timeout,
+        ];
+        let mut codes: Vec<u8> = kinds.iter().map(|k| exit_code(*k)).collect();
+        assert!(codes.iter().all(|c| *c != 0 && *c != 2));
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), kinds.len());
+    }
+
+    #[test]
+    fn rejects_unsupported_record_types() {
+        let err = record_request(args("LOC", "x")).unwrap_err();
+        assert_eq!(err.kind(), ApiErrorKind::Invalid);
+    }
+
+    #[test]
+    fn rejects_bad_address_content() {
+        let err = record_request(args("a", "::1")).unwrap_err();
+        assert_eq!(err.kind(), ApiErrorKind::Invalid);
+        let err = record_request(args("AAAA", "192.0.2.1")).unwrap_err();
+        assert_eq!(err.kind(), ApiErrorKind::Invalid);
+        assert!(matches!(
+            record_request(args("aaaa", "2001:db8::1")).unwrap(),
+            RecordRequest::AAAA(r) if r.content == Some("2001:db8::1".parse().unwrap())
+        ));
+    }
+
+    #[test]
+    fn passes_mx_priority_through() {
+        let mut mx = args("mx", "mail.example.com");
+        mx.priority = Some(10);
+        match record_request(mx).unwrap() {
+            RecordRequest::MX(r) => {
+                assert_eq!(r.priority, Some(10));
+                assert_eq!(r.content.as_deref(), Some("mail.example.com"));
+            }
+            _ => panic!("expected an MX request"),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use hyper::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiError, ApiResponse, ApiResult, Cloudflare, PerformOptions, ZoneId};
+
+/// Items per purge request allowed on every plan. Enterprise zones accept more; raise it with
+/// [`CloudflareCache::with_chunk_size`].
+pub const DEFAULT_PURGE_CHUNK_SIZE: usize = 30;
+
+/// A URL to purge, optionally with the headers that make up its cache key.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PurgeFile {
+    Url(String),
+    WithHeaders {
+        url: String,
+        headers: HashMap<String, String>,
+    },
+}
+
+impl From<&str> for PurgeFile {
+    fn from(url: &str) -> Self {
+        Self::Url(url.to_string())
+    }
+}
+
+impl From<String> for PurgeFile {
+    fn from(url: String) -> Self {
+        Self::Url(url)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PurgeCacheRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purge_everything: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PurgeFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefixes: Vec<String>,
+}
+
+impl PurgeCacheRequest {
+    pub fn len(&self) -> usize {
+        self.files.len() + self.tags.len() + self.hosts.len() + self.prefixes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.purge_everything != Some(true) && self.len() == 0
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PurgeResult {
+    pub id: String,
+}
+
+/// What to purge. Cache tags, hosts and prefixes need an Enterprise plan.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PurgeTarget {
+    Everything,
+    Files(Vec<PurgeFile>),
+    Tags(Vec<String>),
+    Hosts(Vec<String>),
+    /// URL prefixes. The scheme is dropped, as the API expects `example.com/path`.
+    Prefixes(Vec<String>),
+}
+
+impl PurgeTarget {
+    pub fn urls<I, S>(urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Files(urls.into_iter().map(|u| PurgeFile::Url(u.into())).collect())
+    }
+
+    /// Splits the target into requests of at most `chunk_size` items.
+    pub fn chunks(&self, chunk_size: usize) -> Vec<PurgeCacheRequest> {
+        let chunk_size = chunk_size.max(1);
+        let split = |items: &[String], f: fn(Vec<String>) -> PurgeCacheRequest| {
+            items
+                .chunks(chunk_size)
+                .map(|c| f(c.to_vec()))
+                .collect::<Vec<_>>()
+        };
+        match self {
+            Self::Everything => vec![PurgeCacheRequest {
+                purge_everything: Some(true),
+                ..Default::default()
+            }],
+            Self::Files(files) => files
+                .chunks(chunk_size)
+                .map(|c| PurgeCacheRequest {
+                    files: c.to_vec(),
+                    ..Default::default()
+                })
+                .collect(),
+            Self::Tags(tags) => split(tags, |tags| PurgeCacheRequest {
+                tags,
+                ..Default::default()
+            }),
+            Self::Hosts(hosts) => split(hosts, |hosts| PurgeCacheRequest {
+                hosts,
+                ..Default::default()
+            }),
+            Self::Prefixes(prefixes) => {
+                let prefixes: Vec<String> = prefixes
+                    .iter()
+                    .map(|p| {
+                        let p = p.trim();
+                        p.strip_prefix("https://")
+                            .or_else(|| p.strip_prefix("http://"))
+                            .unwrap_or(p)
+                            .to_string()
+                    })
+                    .collect();
+                split(&prefixes, |prefixes| PurgeCacheRequest {
+                    prefixes,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+}
+
+/// Outcome of every purge request, in the order they were sent.
+#[derive(Debug, Default)]
+pub struct PurgeReport {
+    pub results: Vec<(PurgeCacheRequest, ApiResult<PurgeResult>)>,
+}
+
+impl PurgeReport {
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|(_, r)| r.is_ok())
+    }
+
+    /// Items covered by requests that succeeded.
+    pub fn purged(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, r)| r.is_ok())
+            .map(|(req, _)| req.len())
+            .sum()
+    }
+
+    /// Requests that failed, ready to be sent again with [`CloudflareCache::purge_request`].
+    pub fn failed_requests(&self) -> Vec<PurgeCacheRequest> {
+        self.results
+            .iter()
+            .filter(|(_, r)| r.is_err())
+            .map(|(req, _)| req.clone())
+            .collect()
+    }
+
+    pub fn into_result(self) -> ApiResult<Vec<PurgeResult>> {
+        let total = self.results.len();
+        let mut ok = vec![];
+        let mut failed = vec![];
+        for (i, (_, r)) in self.results.into_iter().enumerate() {
+            match r {
+                Ok(r) => ok.push(r),
+                Err(e) => failed.push((i, e)),
+            }
+        }
+        match failed.as_slice() {
+            [] => Ok(ok),
+            [(_, e), ..] => Err(ApiError::with_kind(
+                e.kind(),
+                failed
+                    .iter()
+                    .map(|(i, e)| format!("purge {} of {} failed: {}", i + 1, total, e.message()))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            )),
+        }
+    }
+}
+
+pub struct CloudflareCache {
+    pub(crate) c: Cloudflare,
+    pub(crate) chunk_size: usize,
+}
+
+impl CloudflareCache {
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub async fn purge_request(
+        &self,
+        zone_id: &ZoneId,
+        req: PurgeCacheRequest,
+    ) -> ApiResult<ApiResponse<PurgeResult>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::POST,
+                PerformOptions {
+                    path: format!("zones/{}/purge_cache", zone_id),
+                    params: None,
+                    body: Some(req),
+                },
+            )
+            .await
+    }
+
+    /// Sends one request per chunk. Purging is idempotent, so a failed chunk does not stop
+    /// the rest; check the report, or resend [`PurgeReport::failed_requests`].
+    pub async fn purge(&self, zone_id: &ZoneId, target: PurgeTarget) -> PurgeReport {
+        let mut report = PurgeReport::default();
+        for req in target.chunks(self.chunk_size) {
+            let res = self
+                .purge_request(zone_id, req.clone())
+                .await
+                .and_then(ApiResponse::into_result);
+            report.results.push((req, res));
+        }
+        report
+    }
+
+    pub async fn purge_everything(&self, zone_id: &ZoneId) -> ApiResult<PurgeResult> {
+        let mut results = self
+            .purge(zone_id, PurgeTarget::Everything)
+            .await
+            .into_result()?;
+        Ok(results.remove(0))
+    }
+
+    pub async fn purge_urls<I, S>(&self, zone_id: &ZoneId, urls: I) -> PurgeReport
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.purge(zone_id, PurgeTarget::urls(urls)).await
+    }
+
+    pub async fn purge_files(&self, zone_id: &ZoneId, files: Vec<PurgeFile>) -> PurgeReport {
+        self.purge(zone_id, PurgeTarget::Files(files)).await
+    }
+
+    pub async fn purge_tags(&self, zone_id: &ZoneId, tags: Vec<String>) -> PurgeReport {
+        self.purge(zone_id, PurgeTarget::Tags(tags)).await
+    }
+
+    pub async fn purge_hosts(&self, zone_id: &ZoneId, hosts: Vec<String>) -> PurgeReport {
+        self.purge(zone_id, PurgeTarget::Hosts(hosts)).await
+    }
+
+    pub async fn purge_prefixes(&self, zone_id: &ZoneId, prefixes: Vec<String>) -> PurgeReport {
+        self.purge(zone_id, PurgeTarget::Prefixes(prefixes)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_targets() {
+        let urls: Vec<String> = (0..65)
+            .map(|i| format!("https://example.com/{}", i))
+            .collect();
+        let chunks = PurgeTarget::urls(urls).chunks(DEFAULT_PURGE_CHUNK_SIZE);
+        let sizes: Vec<usize> = chunks.iter().map(PurgeCacheRequest::len).collect();
+        assert_eq!(sizes, vec![30, 30, 5]);
+        assert_eq!(
+            chunks[2].files[0],
+            PurgeFile::Url("https://example.com/60".to_string())
+        );
+
+        let prefixes = PurgeTarget::Prefixes(vec![
+            "https://example.com/assets".to_string(),
+            "example.com/img".to_string(),
+        ]);
+        assert_eq!(
+            serde_json::to_value(&prefixes.chunks(30)[0]).unwrap(),
+            serde_json::json!({"prefixes": ["example.com/assets", "example.com/img"]})
+        );
+
+        let everything = PurgeTarget::Everything.chunks(30);
+        assert_eq!(
+            serde_json::to_value(&everything).unwrap(),
+            serde_json::json!([{"purge_everything": true}])
+        );
+        assert!(PurgeTarget::Tags(vec![]).chunks(30).is_empty());
+    }
+
+    #[test]
+    fn aggregates_results() {
+        let req = |tag: &str| PurgeCacheRequest {
+            tags: vec![tag.to_string(), format!("{}-2", tag)],
+            ..Default::default()
+        };
+        let report = PurgeReport {
+            results: vec![
+                (
+                    req("a"),
+                    Ok(PurgeResult {
+                        id: "1".to_string(),
+                    }),
+                ),
+                (req("b"), Err(ApiError::new("rate limited"))),
+            ],
+        };
+        assert!(!report.is_success());
+        assert_eq!(report.purged(), 2);
+        assert_eq!(report.failed_requests(), vec![req("b")]);
+        let err = report.into_result().unwrap_err();
+        assert_eq!(err.message(), "purge 2 of 2 failed: rate limited");
+    }
+}
@@ -0,0 +1,399 @@
+use std::fmt::Display;
+#[cfg(feature = "config")]
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AccountId, ApiError, ApiErrorKind, Cloudflare};
+
+pub const ENV_API_TOKEN: &str = "CLOUDFLARE_API_TOKEN";
+pub const ENV_EMAIL: &str = "CLOUDFLARE_EMAIL";
+pub const ENV_API_KEY: &str = "CLOUDFLARE_API_KEY";
+pub const ENV_ACCOUNT_ID: &str = "CLOUDFLARE_ACCOUNT_ID";
+pub const ENV_PROFILE: &str = "CLOUDFLARE_PROFILE";
+pub const ENV_CONFIG: &str = "CLOUDFLARE_CONFIG";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError(String);
+
+impl ConfigError {
+    fn new(msg: impl ToString) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConfigError: {}", self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ConfigError> for ApiError {
+    fn from(e: ConfigError) -> Self {
+        ApiError::with_kind(ApiErrorKind::Invalid, e.0)
+    }
+}
+
+/// Either an API token, or an account email with a global API key. A token wins when both
+/// are set. `Debug` output redacts the token and key.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Credentials {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<AccountId>,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redact = |v: &Option<String>| v.as_ref().map(|_| "<redacted>");
+        f.debug_struct("Credentials")
+            .field("api_token", &redact(&self.api_token))
+            .field("email", &self.email)
+            .field("api_key", &redact(&self.api_key))
+            .field("account_id", &self.account_id)
+            .finish()
+    }
+}
+
+impl Credentials {
+    /// Reads `CLOUDFLARE_API_TOKEN`, `CLOUDFLARE_EMAIL`, `CLOUDFLARE_API_KEY` and
+    /// `CLOUDFLARE_ACCOUNT_ID`. Empty variables count as unset.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|k| std::env::var(k).ok())
+    }
+
+    fn from_vars(get: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let get = |k: &str| get(k).filter(|v| !v.trim().is_empty());
+        let account_id = get(ENV_ACCOUNT_ID)
+            .map(|v| {
+                AccountId::try_from(v)
+                    .map_err(|e| ConfigError::new(format!("{}: {}", ENV_ACCOUNT_ID, e)))
+            })
+            .transpose()?;
+        Ok(Self {
+            api_token: get(ENV_API_TOKEN),
+            email: get(ENV_EMAIL),
+            api_key: get(ENV_API_KEY),
+            account_id,
+        })
+    }
+
+    fn has_auth(&self) -> bool {
+        self.api_token.is_some() || self.email.is_some() || self.api_key.is_some()
+    }
+
+    /// Fills in from `fallback` whatever `self` leaves unset. Auth is taken as a whole, so a
+    /// token here is never mixed with an email and key from the fallback.
+    pub fn or(self, fallback: Credentials) -> Self {
+        let account_id = self.account_id.clone().or(fallback.account_id.clone());
+        let auth = if self.has_auth() { self } else { fallback };
+        Self { account_id, ..auth }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.api_token.is_some() {
+            return Ok(());
+        }
+        match (&self.email, &self.api_key) {
+            (Some(_), Some(_)) => Ok(()),
+            (Some(email), None) => Err(ConfigError::new(format!(
+                "email {} is set without an API key: set {} or api_key",
+                email, ENV_API_KEY
+            ))),
+            (None, Some(_)) => Err(ConfigError::new(format!(
+                "API key is set without an email: set {} or email",
+                ENV_EMAIL
+            ))),
+            (None, None) => Err(ConfigError::new(format!(
+                "no credentials: set {}, or {} and {}",
+                ENV_API_TOKEN, ENV_EMAIL, ENV_API_KEY
+            ))),
+        }
+    }
+
+    pub fn client(&self) -> Result<Cloudflare, ConfigError> {
+        self.validate()?;
+        let mut c = Cloudflare::default();
+        if let Some(token) = &self.api_token {
+            c = c.with_token(token);
+        } else if let (Some(email), Some(key)) = (&self.email, &self.api_key) {
+            c = c.with_email(email).with_api_key(key);
+        }
+        if let Some(account_id) = &self.account_id {
+            c = c.with_account_id(account_id.clone());
+        }
+        Ok(c)
+    }
+
+    /// Environment variables first, then the selected profile of the config file. The
+    /// profile is `profile`, else `CLOUDFLARE_PROFILE`, else the file's `default_profile`,
+    /// else `default`. A profile named by `profile` or `CLOUDFLARE_PROFILE` reverses the
+    /// order, so its credentials win over the environment. The file is `path`, else
+    /// `CLOUDFLARE_CONFIG`, else [`ConfigFile::default_path`]; a missing file is only an
+    /// error when named explicitly.
+    #[cfg(feature = "config")]
+    pub fn load(path: Option<&Path>, profile: Option<&str>) -> Result<Self, ConfigError> {
+        let env = Self::from_env()?;
+        let env_profile = std::env::var(ENV_PROFILE).ok().filter(|p| !p.is_empty());
+        let profile = profile.map(str::to_string).or(env_profile);
+        let env_path = std::env::var_os(ENV_CONFIG).map(PathBuf::from);
+        let explicit = path.map(Path::to_path_buf).or(env_path);
+
+        let file = match (&explicit, ConfigFile::default_path()) {
+            (Some(p), _) => Some(ConfigFile::load(p)?),
+            (None, Some(p)) if p.exists() => Some(ConfigFile::load(&p)?),
+            _ => None,
+        };
+        Self::merge(env, file.as_ref(), profile.as_deref())
+    }
+
+    #[cfg(feature = "config")]
+    fn merge(
+        env: Credentials,
+        file: Option<&ConfigFile>,
+        profile: Option<&str>,
+    ) -> Result<Self, ConfigError> {
+        let creds = match (file, profile) {
+            (Some(file), Some(name)) => file.profile(Some(name))?.clone().or(env),
+            (Some(file), None) => env.or(file.profile(None)?.clone()),
+            (None, Some(name)) => {
+                return Err(ConfigError::new(format!(
+                    "profile {} requested but no config file was found",
+                    name
+                )))
+            }
+            (None, None) => env,
+        };
+        creds.validate()?;
+        Ok(creds)
+    }
+}
+
+/// Config file with named profiles:
+///
+/// ```toml
+/// default_profile = "work"
+///
+/// [profiles.work]
+/// api_token = "..."
+/// account_id = "..."
+///
+/// [profiles.personal]
+/// email = "me@example.com"
+/// api_key = "..."
+/// ```
+///
+/// Credentials at the top level act as the `default` profile.
+#[cfg(feature = "config")]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ConfigFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: HashMap<String, Credentials>,
+    #[serde(flatten)]
+    pub default: Credentials,
+}
+
+#[cfg(feature = "config")]
+impl ConfigFile {
+    /// `$XDG_CONFIG_HOME/cloudflare/config.toml`, else `~/.config/cloudflare/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("cloudflare").join("config.toml"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let s = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::new(format!("{}: {}", path.display(), e)))?;
+        Self::parse(&s).map_err(|e| ConfigError::new(format!("{}: {}", path.display(), e.0)))
+    }
+
+    pub fn parse(s: &str) -> Result<Self, ConfigError> {
+        toml::from_str(s).map_err(|e| ConfigError::new(e.message()))
+    }
+
+    pub fn profile(&self, name: Option<&str>) -> Result<&Credentials, ConfigError> {
+        let explicit = name.or(self.default_profile.as_deref());
+        let name = explicit.unwrap_or("default");
+        if let Some(creds) = self.profiles.get(name) {
+            return Ok(creds);
+        }
+        if name == "default" && (explicit.is_none() || self.default.has_auth()) {
+            return Ok(&self.default);
+        }
+        let mut known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+        known.sort();
+        Err(ConfigError::new(format!(
+            "no profile named {} (known profiles: {})",
+            name,
+            if known.is_empty() {
+                "none".to_string()
+            } else {
+                known.join(", ")
+            }
+        )))
+    }
+}
+
+impl Cloudflare {
+    /// Builds a client from the `CLOUDFLARE_*` environment variables, see
+    /// [`Credentials::from_env`].
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Credentials::from_env()?.client()
+    }
+
+    /// Builds a client from the environment and config file, see [`Credentials::load`].
+    #[cfg(feature = "config")]
+    pub fn from_profile(profile: Option<&str>) -> Result<Self, ConfigError> {
+        Credentials::load(None, profile)?.client()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "0123456789abcdef0123456789abcdef";
+
+    fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let pairs: Vec<(String, String)> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |k| pairs.iter().find(|(n, _)| n == k).map(|(_, v)| v.clone())
+    }
+
+    #[test]
+    fn env_credentials_validate() {
+        let creds =
+            Credentials::from_vars(vars(&[(ENV_API_TOKEN, "tok"), (ENV_EMAIL, "")])).unwrap();
+        assert_eq!(creds.api_token.as_deref(), Some("tok"));
+        assert_eq!(creds.email, None);
+        assert!(creds.validate().is_ok());
+
+        let creds = Credentials::from_vars(vars(&[(ENV_EMAIL, "a@b.c")])).unwrap();
+        assert!(creds.validate().unwrap_err().0.contains(ENV_API_KEY));
+        assert!(Credentials::default().validate().is_err());
+        assert!(Credentials::from_vars(vars(&[(ENV_ACCOUNT_ID, "nope")])).is_err());
+    }
+
+    #[test]
+    fn env_auth_wins_as_a_whole() {
+        let env = Credentials {
+            email: Some("a@b.c".to_string()),
+            api_key: Some("key".to_string()),
+            ..Default::default()
+        };
+        let profile = Credentials {
+            api_token: Some("tok".to_string()),
+            account_id: Some(AccountId::try_from(ACCOUNT).unwrap()),
+            ..Default::default()
+        };
+        let merged = env.or(profile.clone());
+        assert_eq!(merged.api_token, None);
+        assert_eq!(merged.api_key.as_deref(), Some("key"));
+        assert_eq!(merged.account_id, profile.account_id);
+
+        let merged = Credentials::default().or(profile.clone());
+        assert_eq!(merged, profile);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn selects_profiles() {
+        let file = ConfigFile::parse(&format!(
+            r#"
+api_token = "top"
+default_profile = "work"
+
+[profiles.work]
+api_token = "work"
+account_id = "{}"
+
+[profiles.personal]
+email = "a@b.c"
+api_key = "key"
+"#,
+            ACCOUNT
+        ))
+        .unwrap();
+        assert_eq!(
+            file.profile(None).unwrap().api_token.as_deref(),
+            Some("work")
+        );
+        assert_eq!(
+            file.profile(Some("personal")).unwrap().api_key.as_deref(),
+            Some("key")
+        );
+        assert_eq!(
+            file.profile(Some("default")).unwrap().api_token.as_deref(),
+            Some("top")
+        );
+        let err = file.profile(Some("missing")).unwrap_err();
+        assert!(err.0.contains("personal, work"), "{}", err);
+
+        let flat = ConfigFile::parse("api_token = \"flat\"").unwrap();
+        assert_eq!(
+            flat.profile(None).unwrap().api_token.as_deref(),
+            Some("flat")
+        );
+        assert!(ConfigFile::parse("profiles = 3").is_err());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn named_profile_wins_over_env() {
+        let file = ConfigFile::parse(
+            r#"
+[profiles.default]
+api_token = "default"
+
+[profiles.work]
+email = "a@b.c"
+api_key = "key"
+"#,
+        )
+        .unwrap();
+        let env = Credentials {
+            api_token: Some("env".to_string()),
+            account_id: Some(AccountId::try_from(ACCOUNT).unwrap()),
+            ..Default::default()
+        };
+
+        let creds = Credentials::merge(env.clone(), Some(&file), Some("work")).unwrap();
+        assert_eq!(creds.api_token, None);
+        assert_eq!(creds.api_key.as_deref(), Some("key"));
+        assert_eq!(creds.account_id, env.account_id);
+
+        let creds = Credentials::merge(env.clone(), Some(&file), None).unwrap();
+        assert_eq!(creds.api_token.as_deref(), Some("env"));
+        assert!(Credentials::merge(env, None, Some("work")).is_err());
+    }
+
+    #[test]
+    fn debug_redacts_secrets() {
+        let creds = Credentials {
+            api_token: Some("tok-secret".to_string()),
+            email: Some("a@b.c".to_string()),
+            api_key: Some("key-secret".to_string()),
+            account_id: None,
+        };
+        let out = format!("{:?}", creds);
+        assert!(!out.contains("secret"), "{}", out);
+        assert!(out.contains("a@b.c"), "{}", out);
+    }
+}
@@ -0,0 +1,485 @@
+use std::{collections::HashMap, fmt::Display};
+
+use hyper::Method;
+use serde::{Deserialize, Serialize};
+
+use super::SortDirection;
+use crate::{ApiResponse, ApiResult, Cloudflare, PerformOptions, QueryParams, Timestamp, ZoneId};
+
+/// Implemented by [`DnsAnalyticsDimension`] and [`DnsAnalyticsMetric`], so either can be used
+/// to sort a report.
+pub trait AnalyticsField {
+    fn field_name(&self) -> &'static str;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsAnalyticsDimension {
+    QueryName,
+    QueryType,
+    ResponseCode,
+    ResponseCached,
+    ColoName,
+    Origin,
+    DayOfWeek,
+    Tcp,
+    IpVersion,
+    QuerySizeBucket,
+    ResponseSizeBucket,
+}
+
+impl AnalyticsField for DnsAnalyticsDimension {
+    fn field_name(&self) -> &'static str {
+        match self {
+            Self::QueryName => "queryName",
+            Self::QueryType => "queryType",
+            Self::ResponseCode => "responseCode",
+            Self::ResponseCached => "responseCached",
+            Self::ColoName => "coloName",
+            Self::Origin => "origin",
+            Self::DayOfWeek => "dayOfWeek",
+            Self::Tcp => "tcp",
+            Self::IpVersion => "ipVersion",
+            Self::QuerySizeBucket => "querySizeBucket",
+            Self::ResponseSizeBucket => "responseSizeBucket",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsAnalyticsMetric {
+    QueryCount,
+    UncachedCount,
+    StaleCount,
+    ResponseTimeAvg,
+    ResponseTimeMedian,
+    ResponseTime90th,
+    ResponseTime99th,
+}
+
+impl AnalyticsField for DnsAnalyticsMetric {
+    fn field_name(&self) -> &'static str {
+        match self {
+            Self::QueryCount => "queryCount",
+            Self::UncachedCount => "uncachedCount",
+            Self::StaleCount => "staleCount",
+            Self::ResponseTimeAvg => "responseTimeAvg",
+            Self::ResponseTimeMedian => "responseTimeMedian",
+            Self::ResponseTime90th => "responseTime90th",
+            Self::ResponseTime99th => "responseTime99th",
+        }
+    }
+}
+
+/// Bucket width for [`CloudflareDnsAnalytics::report_by_time`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeDelta {
+    All,
+    Auto,
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Dekaminute,
+    Minute,
+}
+
+impl TimeDelta {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Auto => "auto",
+            Self::Year => "year",
+            Self::Quarter => "quarter",
+            Self::Month => "month",
+            Self::Week => "week",
+            Self::Day => "day",
+            Self::Hour => "hour",
+            Self::Dekaminute => "dekaminute",
+            Self::Minute => "minute",
+        }
+    }
+}
+
+/// Filter expression on report dimensions. The API evaluates `and` (`;`) and `or` (`,`) from
+/// left to right without grouping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyticsFilter(String);
+
+impl AnalyticsFilter {
+    fn compare(dimension: DnsAnalyticsDimension, op: &str, value: &str) -> Self {
+        Self(format!("{}{}{}", dimension.field_name(), op, value))
+    }
+
+    pub fn eq(dimension: DnsAnalyticsDimension, value: &str) -> Self {
+        Self::compare(dimension, "==", value)
+    }
+
+    pub fn ne(dimension: DnsAnalyticsDimension, value: &str) -> Self {
+        Self::compare(dimension, "!=", value)
+    }
+
+    pub fn gt(dimension: DnsAnalyticsDimension, value: &str) -> Self {
+        Self::compare(dimension, ">", value)
+    }
+
+    pub fn lt(dimension: DnsAnalyticsDimension, value: &str) -> Self {
+        Self::compare(dimension, "<", value)
+    }
+
+    pub fn ge(dimension: DnsAnalyticsDimension, value: &str) -> Self {
+        Self::compare(dimension, ">=", value)
+    }
+
+    pub fn le(dimension: DnsAnalyticsDimension, value: &str) -> Self {
+        Self::compare(dimension, "<=", value)
+    }
+
+    /// An expression in the API's own syntax, e.g. `responseCode==NXDOMAIN`.
+    pub fn raw(expr: &str) -> Self {
+        Self(expr.to_string())
+    }
+
+    pub fn and(self, other: AnalyticsFilter) -> Self {
+        Self(format!("{};{}", self.0, other.0))
+    }
+
+    pub fn or(self, other: AnalyticsFilter) -> Self {
+        Self(format!("{},{}", self.0, other.0))
+    }
+}
+
+impl Display for AnalyticsFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DnsAnalyticsQuery {
+    pub dimensions: Vec<DnsAnalyticsDimension>,
+    pub metrics: Vec<DnsAnalyticsMetric>,
+    pub since: Option<Timestamp>,
+    pub until: Option<Timestamp>,
+    pub filters: Option<AnalyticsFilter>,
+    pub sort: Vec<(&'static str, SortDirection)>,
+    pub limit: Option<usize>,
+    /// Only used by the by-time report.
+    pub time_delta: Option<TimeDelta>,
+}
+
+impl DnsAnalyticsQuery {
+    pub fn new(metrics: &[DnsAnalyticsMetric]) -> Self {
+        Self {
+            metrics: metrics.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_dimensions(mut self, dimensions: &[DnsAnalyticsDimension]) -> Self {
+        self.dimensions = dimensions.to_vec();
+        self
+    }
+
+    /// Limits the report to `since..until`. Cloudflare defaults to the last six hours.
+    pub fn with_range(mut self, since: Timestamp, until: Timestamp) -> Self {
+        self.since = Some(since);
+        self.until = Some(until);
+        self
+    }
+
+    pub fn with_filters(mut self, filters: AnalyticsFilter) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    /// Adds a sort key; earlier keys take precedence.
+    pub fn with_sort(mut self, field: impl AnalyticsField, direction: SortDirection) -> Self {
+        self.sort.push((field.field_name(), direction));
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_time_delta(mut self, time_delta: TimeDelta) -> Self {
+        self.time_delta = Some(time_delta);
+        self
+    }
+
+    fn params(&self) -> QueryParams {
+        let join = |fields: Vec<&str>| fields.join(",");
+        let mut params = vec![];
+        if !self.dimensions.is_empty() {
+            let dims = self.dimensions.iter().map(|d| d.field_name()).collect();
+            params.push(("dimensions".to_string(), join(dims)));
+        }
+        if !self.metrics.is_empty() {
+            let metrics = self.metrics.iter().map(|m| m.field_name()).collect();
+            params.push(("metrics".to_string(), join(metrics)));
+        }
+        for (key, ts) in [("since", &self.since), ("until", &self.until)] {
+            if let Some(ts) = ts {
+                params.push((key.to_string(), ts.to_string()));
+            }
+        }
+        if let Some(filters) = &self.filters {
+            params.push(("filters".to_string(), filters.to_string()));
+        }
+        if !self.sort.is_empty() {
+            let sort: Vec<String> = self
+                .sort
+                .iter()
+                .map(|(field, direction)| match direction {
+                    SortDirection::Asc => format!("+{}", field),
+                    SortDirection::Desc => format!("-{}", field),
+                })
+                .collect();
+            params.push(("sort".to_string(), sort.join(",")));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit".to_string(), limit.to_string()));
+        }
+        if let Some(time_delta) = self.time_delta {
+            params.push(("time_delta".to_string(), time_delta.as_str().to_string()));
+        }
+        params
+    }
+}
+
+/// The query as the API understood it, with defaults filled in.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DnsAnalyticsQueryInfo {
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+    #[serde(default)]
+    pub metrics: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<String>,
+    #[serde(default)]
+    pub sort: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_delta: Option<String>,
+}
+
+impl DnsAnalyticsQueryInfo {
+    fn metric_index(&self, metric: DnsAnalyticsMetric) -> Option<usize> {
+        self.metrics.iter().position(|m| m == metric.field_name())
+    }
+
+    fn dimension_index(&self, dimension: DnsAnalyticsDimension) -> Option<usize> {
+        self.dimensions
+            .iter()
+            .position(|d| d == dimension.field_name())
+    }
+}
+
+/// One row per combination of dimension values, with metrics in query order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DnsAnalyticsRow {
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+    #[serde(default)]
+    pub metrics: Vec<f64>,
+}
+
+/// Like [`DnsAnalyticsRow`], with one series per metric, one value per time interval.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DnsAnalyticsTimeRow {
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+    #[serde(default)]
+    pub metrics: Vec<Vec<f64>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DnsAnalyticsReport {
+    #[serde(default)]
+    pub data: Vec<DnsAnalyticsRow>,
+    /// Seconds the data lags behind real time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_lag: Option<f64>,
+    #[serde(default)]
+    pub max: HashMap<String, f64>,
+    #[serde(default)]
+    pub min: HashMap<String, f64>,
+    #[serde(default)]
+    pub totals: HashMap<String, f64>,
+    #[serde(default)]
+    pub rows: usize,
+    #[serde(default)]
+    pub query: DnsAnalyticsQueryInfo,
+}
+
+impl DnsAnalyticsReport {
+    pub fn metric(&self, row: &DnsAnalyticsRow, metric: DnsAnalyticsMetric) -> Option<f64> {
+        row.metrics.get(self.query.metric_index(metric)?).copied()
+    }
+
+    pub fn dimension<'a>(
+        &self,
+        row: &'a DnsAnalyticsRow,
+        dimension: DnsAnalyticsDimension,
+    ) -> Option<&'a str> {
+        row.dimensions
+            .get(self.query.dimension_index(dimension)?)
+            .map(String::as_str)
+    }
+
+    pub fn total(&self, metric: DnsAnalyticsMetric) -> Option<f64> {
+        self.totals.get(metric.field_name()).copied()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DnsAnalyticsByTime {
+    #[serde(default)]
+    pub data: Vec<DnsAnalyticsTimeRow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_lag: Option<f64>,
+    #[serde(default)]
+    pub max: HashMap<String, f64>,
+    #[serde(default)]
+    pub min: HashMap<String, f64>,
+    #[serde(default)]
+    pub totals: HashMap<String, f64>,
+    #[serde(default)]
+    pub rows: usize,
+    #[serde(default)]
+    pub query: DnsAnalyticsQueryInfo,
+    /// `[start, end]` of each interval, matching the series in every row.
+    #[serde(default)]
+    pub time_intervals: Vec<Vec<Timestamp>>,
+}
+
+impl DnsAnalyticsByTime {
+    pub fn series<'a>(
+        &self,
+        row: &'a DnsAnalyticsTimeRow,
+        metric: DnsAnalyticsMetric,
+    ) -> Option<&'a [f64]> {
+        row.metrics
+            .get(self.query.metric_index(metric)?)
+            .map(Vec::as_slice)
+    }
+
+    pub fn dimension<'a>(
+        &self,
+        row: &'a DnsAnalyticsTimeRow,
+        dimension: DnsAnalyticsDimension,
+    ) -> Option<&'a str> {
+        row.dimensions
+            .get(self.query.dimension_index(dimension)?)
+            .map(String::as_str)
+    }
+
+    pub fn total(&self, metric: DnsAnalyticsMetric) -> Option<f64> {
+        self.totals.get(metric.field_name()).copied()
+    }
+}
+
+pub struct CloudflareDnsAnalytics {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareDnsAnalytics {
+    pub async fn report(
+        &self,
+        zone_id: &ZoneId,
+        query: &DnsAnalyticsQuery,
+    ) -> ApiResult<ApiResponse<DnsAnalyticsReport>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dns_analytics/report", zone_id),
+                    params: Some(query.params()),
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn report_by_time(
+        &self,
+        zone_id: &ZoneId,
+        query: &DnsAnalyticsQuery,
+    ) -> ApiResult<ApiResponse<DnsAnalyticsByTime>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dns_analytics/report/bytime", zone_id),
+                    params: Some(query.params()),
+                    body: None,
+                },
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DnsAnalyticsDimension::*;
+    use DnsAnalyticsMetric::*;
+
+    #[test]
+    fn builds_query_params() {
+        let params: HashMap<String, String> =
+            DnsAnalyticsQuery::new(&[QueryCount, ResponseTimeAvg])
+                .with_dimensions(&[QueryName, ResponseCode])
+                .with_filters(
+                    AnalyticsFilter::eq(QueryType, "A")
+                        .and(AnalyticsFilter::ne(ResponseCode, "NOERROR")),
+                )
+                .with_sort(QueryCount, SortDirection::Desc)
+                .with_sort(QueryName, SortDirection::Asc)
+                .with_limit(10)
+                .with_time_delta(TimeDelta::Hour)
+                .params()
+                .into_iter()
+                .collect();
+        assert_eq!(params["dimensions"], "queryName,responseCode");
+        assert_eq!(params["metrics"], "queryCount,responseTimeAvg");
+        assert_eq!(params["filters"], "queryType==A;responseCode!=NOERROR");
+        assert_eq!(params["sort"], "-queryCount,+queryName");
+        assert_eq!(params["limit"], "10");
+        assert_eq!(params["time_delta"], "hour");
+        assert!(!params.contains_key("since"));
+    }
+
+    #[test]
+    fn reads_values_by_name() {
+        let report: DnsAnalyticsReport = serde_json::from_str(
+            r#"{
+                "data": [{"dimensions": ["www.example.com", "NOERROR"], "metrics": [42, 1.5]}],
+                "data_lag": 60,
+                "rows": 1,
+                "totals": {"queryCount": 42, "responseTimeAvg": 1.5},
+                "query": {
+                    "dimensions": ["queryName", "responseCode"],
+                    "metrics": ["queryCount", "responseTimeAvg"],
+                    "limit": 100
+                }
+            }"#,
+        )
+        .unwrap();
+        let row = &report.data[0];
+        assert_eq!(report.metric(row, ResponseTimeAvg), Some(1.5));
+        assert_eq!(report.dimension(row, QueryName), Some("www.example.com"));
+        assert_eq!(report.dimension(row, ColoName), None);
+        assert_eq!(report.total(QueryCount), Some(42.0));
+    }
+}
@@ -0,0 +1,260 @@
+use hyper::Method;
+use serde::{Deserialize, Serialize};
+
+use super::{RecordMessage, RecordRequest};
+use crate::{ApiError, ApiResponse, ApiResult, Cloudflare, PerformOptions, RecordId, ZoneId};
+
+/// Operations per request accepted by the batch endpoint on Free plans. Paid plans allow more;
+/// raise it with [`BatchBuilder::with_chunk_size`].
+pub const DEFAULT_BATCH_CHUNK_SIZE: usize = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BatchDelete {
+    pub id: RecordId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BatchUpdate {
+    pub id: RecordId,
+    #[serde(flatten)]
+    pub record: RecordRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BatchRequest {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deletes: Vec<BatchDelete>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patches: Vec<BatchUpdate>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub puts: Vec<BatchUpdate>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub posts: Vec<RecordRequest>,
+}
+
+impl BatchRequest {
+    pub fn len(&self) -> usize {
+        self.deletes.len() + self.patches.len() + self.puts.len() + self.posts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Records returned by the batch endpoint, one per submitted operation and in the same order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BatchResult {
+    #[serde(default)]
+    pub deletes: Vec<RecordMessage>,
+    #[serde(default)]
+    pub patches: Vec<RecordMessage>,
+    #[serde(default)]
+    pub puts: Vec<RecordMessage>,
+    #[serde(default)]
+    pub posts: Vec<RecordMessage>,
+}
+
+impl BatchResult {
+    fn extend(&mut self, other: BatchResult) {
+        self.deletes.extend(other.deletes);
+        self.patches.extend(other.patches);
+        self.puts.extend(other.puts);
+        self.posts.extend(other.posts);
+    }
+}
+
+enum BatchOp {
+    Delete(BatchDelete),
+    Patch(BatchUpdate),
+    Put(BatchUpdate),
+    Post(RecordRequest),
+}
+
+/// Accumulates record operations for the batch endpoint.
+///
+/// Cloudflare applies each request atomically, running deletes, then patches, then puts, then
+/// posts. Batches larger than the chunk size are split into several requests that keep that
+/// order across chunks, but are then only atomic per chunk.
+pub struct BatchBuilder {
+    c: Cloudflare,
+    zone_id: ZoneId,
+    chunk_size: usize,
+    request: BatchRequest,
+}
+
+impl BatchBuilder {
+    pub fn new(c: &Cloudflare, zone_id: ZoneId) -> Self {
+        Self {
+            c: c.clone(),
+            zone_id,
+            chunk_size: DEFAULT_BATCH_CHUNK_SIZE,
+            request: BatchRequest::default(),
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn delete(mut self, id: RecordId) -> Self {
+        self.request.deletes.push(BatchDelete { id });
+        self
+    }
+
+    pub fn patch(mut self, id: RecordId, record: impl Into<RecordRequest>) -> Self {
+        self.request.patches.push(BatchUpdate {
+            id,
+            record: record.into(),
+        });
+        self
+    }
+
+    pub fn put(mut self, id: RecordId, record: impl Into<RecordRequest>) -> Self {
+        self.request.puts.push(BatchUpdate {
+            id,
+            record: record.into(),
+        });
+        self
+    }
+
+    pub fn post(mut self, record: impl Into<RecordRequest>) -> Self {
+        self.request.posts.push(record.into());
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.request.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.request.is_empty()
+    }
+
+    /// Splits the accumulated operations into requests of at most `chunk_size` operations.
+    pub fn chunks(&self) -> Vec<BatchRequest> {
+        let BatchRequest {
+            deletes,
+            patches,
+            puts,
+            posts,
+        } = self.request.clone();
+        let ops = deletes
+            .into_iter()
+            .map(BatchOp::Delete)
+            .chain(patches.into_iter().map(BatchOp::Patch))
+            .chain(puts.into_iter().map(BatchOp::Put))
+            .chain(posts.into_iter().map(BatchOp::Post));
+
+        let mut chunks = vec![];
+        let mut current = BatchRequest::default();
+        for op in ops {
+            if current.len() == self.chunk_size {
+                chunks.push(std::mem::take(&mut current));
+            }
+            match op {
+                BatchOp::Delete(d) => current.deletes.push(d),
+                BatchOp::Patch(p) => current.patches.push(p),
+                BatchOp::Put(p) => current.puts.push(p),
+                BatchOp::Post(p) => current.posts.push(p),
+            }
+        }
+        if !current.is_empty() {
+            chunks.push(current);
+        }
+        chunks
+    }
+
+    /// Submits every chunk in order and stops at the first one that fails. The error names the
+    /// failing chunk, since earlier chunks have already been committed by then.
+    pub async fn submit(self) -> ApiResult<BatchResult> {
+        let chunks = self.chunks();
+        let total = chunks.len();
+        let mut result = BatchResult::default();
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let res: ApiResponse<BatchResult> = self
+                .c
+                .clone()
+                .perform_json(
+                    Method::POST,
+                    PerformOptions {
+                        path: format!("zones/{}/dns_records/batch", self.zone_id),
+                        params: None,
+                        body: Some(chunk),
+                    },
+                )
+                .await?;
+            match res.into_result() {
+                Ok(r) => result.extend(r),
+                Err(e) => {
+                    return Err(ApiError::with_kind(
+                        e.kind(),
+                        format!("batch chunk {} of {} failed: {}", i + 1, total, e.message()),
+                    ))
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::ARecordRequest;
+
+    fn id(n: usize) -> RecordId {
+        format!("{:032x}", n).parse().unwrap()
+    }
+
+    fn zone() -> ZoneId {
+        "023e105f4ecef8ad9ca31a8372d0c353".parse().unwrap()
+    }
+
+    fn record(n: usize) -> ARecordRequest {
+        ARecordRequest {
+            name: Some(format!("host{}.example.com", n)),
+            content: Some(Ipv4Addr::new(192, 0, 2, n as u8)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn chunks_preserve_operation_order() {
+        let mut batch = BatchBuilder::new(&Cloudflare::default(), zone()).with_chunk_size(2);
+        for n in 1..=2 {
+            batch = batch.delete(id(n));
+        }
+        batch = batch
+            .patch(id(3), record(3))
+            .post(record(4))
+            .post(record(5));
+
+        let chunks = batch.chunks();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].deletes.len(), 2);
+        assert_eq!(chunks[1].patches.len(), 1);
+        assert_eq!(chunks[1].posts.len(), 1);
+        assert_eq!(chunks[2].posts.len(), 1);
+    }
+
+    #[test]
+    fn serializes_updates_with_id_and_type() {
+        let batch = BatchBuilder::new(&Cloudflare::default(), zone()).put(id(1), record(1));
+        let body = serde_json::to_value(&batch.chunks()[0]).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "puts": [{
+                    "id": id(1).to_string(),
+                    "type": "A",
+                    "name": "host1.example.com",
+                    "content": "192.0.2.1",
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use log::error;
+use tokio::task::{JoinError, JoinSet};
+
+use super::{RecordMessage, RecordRequest};
+use crate::{ApiError, ApiResult, Cloudflare, RecordId, ZoneId};
+
+pub const DEFAULT_BULK_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone)]
+pub enum BulkOp {
+    Create {
+        zone_id: ZoneId,
+        record: RecordRequest,
+    },
+    Update {
+        zone_id: ZoneId,
+        id: RecordId,
+        record: RecordRequest,
+    },
+    Replace {
+        zone_id: ZoneId,
+        id: RecordId,
+        record: RecordRequest,
+    },
+    Delete {
+        zone_id: ZoneId,
+        id: RecordId,
+    },
+}
+
+#[derive(Debug)]
+pub enum BulkOutcome {
+    /// The call succeeded. Deletes carry no record.
+    Succeeded(Option<Box<RecordMessage>>),
+    Failed(ApiError),
+    /// Never sent because an earlier operation failed with `stop_on_error` set.
+    Skipped,
+}
+
+/// Outcome of every operation, in the order they were submitted.
+#[derive(Debug, Default)]
+pub struct BulkReport {
+    pub results: Vec<(BulkOp, BulkOutcome)>,
+}
+
+impl BulkReport {
+    pub fn succeeded(&self) -> usize {
+        self.count(|o| matches!(o, BulkOutcome::Succeeded(_)))
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|o| matches!(o, BulkOutcome::Failed(_)))
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|o| matches!(o, BulkOutcome::Skipped))
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.succeeded() == self.results.len()
+    }
+
+    fn count(&self, f: fn(&BulkOutcome) -> bool) -> usize {
+        self.results.iter().filter(|(_, o)| f(o)).count()
+    }
+}
+
+/// Runs individual record calls concurrently, for work the batch endpoint does not cover
+/// (operations spanning several zones, say). Every call goes through the client, so a limit
+/// set with [`Cloudflare::with_rate_limit`] applies across all workers.
+pub struct BulkExecutor {
+    c: Cloudflare,
+    concurrency: usize,
+    stop_on_error: bool,
+}
+
+impl BulkExecutor {
+    pub fn new(c: &Cloudflare) -> Self {
+        Self {
+            c: c.clone(),
+            concurrency: DEFAULT_BULK_CONCURRENCY,
+            stop_on_error: false,
+        }
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Stops starting new operations once one has failed. Calls already in flight still finish.
+    pub fn with_stop_on_error(mut self, stop_on_error: bool) -> Self {
+        self.stop_on_error = stop_on_error;
+        self
+    }
+
+    /// Runs `ops`, starting at most `concurrency` at a time. Ops are pulled from the iterator
+    /// only as workers free up.
+    pub async fn run<I>(&self, ops: I) -> BulkReport
+    where
+        I: IntoIterator<Item = BulkOp>,
+    {
+        self.run_with(ops, |c, op| async move { execute(&c, &op).await })
+            .await
+    }
+
+    async fn run_with<I, F, Fut>(&self, ops: I, f: F) -> BulkReport
+    where
+        I: IntoIterator<Item = BulkOp>,
+        F: Fn(Cloudflare, Arc<BulkOp>) -> Fut,
+        Fut: Future<Output = ApiResult<Option<Box<RecordMessage>>>> + Send + 'static,
+    {
+        let failed = Arc::new(AtomicBool::new(false));
+        let mut tasks = JoinSet::new();
+        let mut results: Vec<(Arc<BulkOp>, Option<BulkOutcome>)> = vec![];
+
+        for op in ops {
+            while tasks.len() >= self.concurrency {
+                finish(&mut results, tasks.join_next().await);
+            }
+            let op = Arc::new(op);
+            let i = results.len();
+            if self.stop_on_error && failed.load(Ordering::SeqCst) {
+                results.push((op, Some(BulkOutcome::Skipped)));
+                continue;
+            }
+            results.push((op.clone(), None));
+            let call = f(self.c.clone(), op);
+            let failed = failed.clone();
+            tasks.spawn(async move {
+                let outcome = match call.await {
+                    Ok(r) => BulkOutcome::Succeeded(r),
+                    Err(e) => {
+                        failed.store(true, Ordering::SeqCst);
+                        BulkOutcome::Failed(e)
+                    }
+                };
+                (i, outcome)
+            });
+        }
+        while let Some(res) = tasks.join_next().await {
+            finish(&mut results, Some(res));
+        }
+
+        BulkReport {
+            results: results
+                .into_iter()
+                .map(|(op, outcome)| {
+                    let op = Arc::try_unwrap(op).unwrap_or_else(|op| (*op).clone());
+                    let outcome = outcome.unwrap_or_else(|| {
+                        BulkOutcome::Failed(ApiError::new("operation did not complete"))
+                    });
+                    (op, outcome)
+                })
+                .collect(),
+        }
+    }
+}
+
+fn finish(
+    results: &mut [(Arc<BulkOp>, Option<BulkOutcome>)],
+    res: Option<Result<(usize, BulkOutcome), JoinError>>,
+) {
+    match res {
+        Some(Ok((i, outcome))) => results[i].1 = Some(outcome),
+        Some(Err(e)) => error!("bulk task panicked:{}", e),
+        None => {}
+    }
+}
+
+async fn execute(c: &Cloudflare, op: &BulkOp) -> ApiResult<Option<Box<RecordMessage>>> {
+    let dns = c.dns();
+    match op {
+        BulkOp::Create { zone_id, record } => dns
+            .create_record(zone_id, record.clone())
+            .await?
+            .into_result()
+            .map(|r| Some(Box::new(r))),
+        BulkOp::Update {
+            zone_id,
+            id,
+            record,
+        } => dns
+            .update_record(zone_id, id, record.clone())
+            .await?
+            .into_result()
+            .map(|r| Some(Box::new(r))),
+        BulkOp::Replace {
+            zone_id,
+            id,
+            record,
+        } => dns
+            .replace_record(zone_id, id, record.clone())
+            .await?
+            .into_result()
+            .map(|r| Some(Box::new(r))),
+        BulkOp::Delete { zone_id, id } => dns
+            .delete_record(zone_id, id)
+            .await?
+            .into_result()
+            .map(|_| None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::AtomicUsize, time::Duration};
+
+    use super::*;
+
+    fn delete(n: u8) -> BulkOp {
+        BulkOp::Delete {
+            zone_id: "023e105f4ecef8ad9ca31a8372d0c353".parse().unwrap(),
+            id: format!("{:032x}", n).parse().unwrap(),
+        }
+    }
+
+    fn index(op: &BulkOp) -> u64 {
+        match op {
+            BulkOp::Delete { id, .. } => u64::from_str_radix(id.as_str(), 16).unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_submission_order_and_caps_concurrency() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let exec = BulkExecutor::new(&Cloudflare::default()).with_concurrency(3);
+        let report = exec
+            .run_with((0..10).map(delete), |_, op| {
+                let (running, peak) = (running.clone(), peak.clone());
+                async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    // Later ops finish first.
+                    tokio::time::sleep(Duration::from_millis(100 - 10 * index(&op))).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    if index(&op) == 4 {
+                        return Err(ApiError::new("boom"));
+                    }
+                    Ok(None)
+                }
+            })
+            .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        let order: Vec<u64> = report.results.iter().map(|(op, _)| index(op)).collect();
+        assert_eq!(order, (0..10).collect::<Vec<_>>());
+        assert!(matches!(report.results[4].1, BulkOutcome::Failed(_)));
+        assert_eq!((report.succeeded(), report.failed()), (9, 1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_starting_ops_after_a_failure() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let exec = BulkExecutor::new(&Cloudflare::default())
+            .with_concurrency(2)
+            .with_stop_on_error(true);
+        let report = exec
+            .run_with((0..6).map(delete), |_, op| {
+                let started = started.clone();
+                async move {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    let fail = index(&op) == 0;
+                    let wait = if fail { 10 } else { 50 };
+                    tokio::time::sleep(Duration::from_millis(wait)).await;
+                    if fail {
+                        return Err(ApiError::new("boom"));
+                    }
+                    Ok(None)
+                }
+            })
+            .await;
+
+        // Op 0 fails while op 1 is in flight, so op 1 still finishes and nothing after it is
+        // sent.
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            (report.failed(), report.succeeded(), report.skipped()),
+            (1, 1, 4)
+        );
+        assert!(report.results[2..]
+            .iter()
+            .all(|(_, o)| matches!(o, BulkOutcome::Skipped)));
+    }
+}
@@ -0,0 +1,488 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    pin::Pin,
+    str::FromStr,
+    time::Duration,
+};
+
+use http_body_util::{BodyExt, Empty};
+use hyper::{body::Bytes, Method, Request, Uri};
+use hyper_tls::HttpsConnector;
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use log::{info, warn};
+
+use super::{AAAARecordRequest, ARecordRequest, RecordMessage, RecordRequest};
+use crate::{
+    ApiError, ApiErrorKind, ApiResult, Cloudflare, PerformOptions, V4PagePaginationArray, ZoneId,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IpFamily {
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    fn record_type(&self) -> &'static str {
+        match self {
+            Self::V4 => "A",
+            Self::V6 => "AAAA",
+        }
+    }
+
+    fn matches(&self, ip: &IpAddr) -> bool {
+        matches!(
+            (self, ip),
+            (Self::V4, IpAddr::V4(_)) | (Self::V6, IpAddr::V6(_))
+        )
+    }
+}
+
+pub type IpFuture<'a> = Pin<Box<dyn Future<Output = ApiResult<IpAddr>> + Send + 'a>>;
+
+/// Discovers the public address of this host.
+pub trait IpSource: Send + Sync {
+    fn public_ip(&self, family: IpFamily) -> IpFuture<'_>;
+}
+
+/// Returns preset addresses, for tests or hosts whose address is known some other way.
+#[derive(Debug, Clone, Default)]
+pub struct FixedIpSource {
+    pub v4: Option<Ipv4Addr>,
+    pub v6: Option<Ipv6Addr>,
+}
+
+impl IpSource for FixedIpSource {
+    fn public_ip(&self, family: IpFamily) -> IpFuture<'_> {
+        let ip = match family {
+            IpFamily::V4 => self.v4.map(IpAddr::V4),
+            IpFamily::V6 => self.v6.map(IpAddr::V6),
+        };
+        Box::pin(async move {
+            ip.ok_or_else(|| ApiError::new(format!("no fixed {:?} address", family)))
+        })
+    }
+}
+
+pub const DEFAULT_IP_SOURCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Fetches the address from an HTTP endpoint that answers with either a bare IP address or a
+/// `key=value` body containing an `ip=` line, like Cloudflare's `/cdn-cgi/trace`.
+#[derive(Debug, Clone)]
+pub struct HttpIpSource {
+    urls: HashMap<IpFamily, String>,
+    timeout: Duration,
+}
+
+impl HttpIpSource {
+    pub fn new() -> Self {
+        Self {
+            urls: HashMap::new(),
+            timeout: DEFAULT_IP_SOURCE_TIMEOUT,
+        }
+    }
+
+    pub fn with_url(mut self, family: IpFamily, url: &str) -> Self {
+        self.urls.insert(family, url.to_string());
+        self
+    }
+
+    /// Limit on each lookup, covering the connection and the whole response body.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Cloudflare's trace endpoint, reached over its IPv4 and IPv6 resolver addresses so each
+    /// family is discovered over the matching transport.
+    pub fn cloudflare_trace() -> Self {
+        Self::new()
+            .with_url(IpFamily::V4, "https://1.1.1.1/cdn-cgi/trace")
+            .with_url(IpFamily::V6, "https://[2606:4700:4700::1111]/cdn-cgi/trace")
+    }
+}
+
+impl Default for HttpIpSource {
+    fn default() -> Self {
+        Self::cloudflare_trace()
+    }
+}
+
+impl IpSource for HttpIpSource {
+    fn public_ip(&self, family: IpFamily) -> IpFuture<'_> {
+        Box::pin(async move {
+            let url = self
+                .urls
+                .get(&family)
+                .ok_or_else(|| ApiError::new(format!("no {:?} discovery url", family)))?;
+            let transport = |e| ApiError::with_kind(ApiErrorKind::Transport, e);
+            let uri =
+                Uri::from_str(url).map_err(|e| ApiError::with_kind(ApiErrorKind::Invalid, e))?;
+            let req = Request::builder()
+                .method(Method::GET)
+                .uri(uri)
+                .body(Empty::<Bytes>::new())
+                .map_err(ApiError::new)?;
+            let client: Client<HttpsConnector<HttpConnector>, Empty<Bytes>> =
+                Client::builder(hyper_util::rt::TokioExecutor::new()).build(HttpsConnector::new());
+            let fetch = async {
+                let res = client
+                    .request(req)
+                    .await
+                    .map_err(|e| transport(e.to_string()))?;
+                res.collect()
+                    .await
+                    .map_err(|e| transport(e.to_string()))
+                    .map(|b| b.to_bytes())
+            };
+            let body = tokio::time::timeout(self.timeout, fetch)
+                .await
+                .map_err(|_| transport(format!("{} timed out after {:?}", url, self.timeout)))??;
+            let ip = parse_ip_response(&String::from_utf8_lossy(&body))
+                .ok_or_else(|| ApiError::new(format!("no address in response from {}", url)))?;
+            if !family.matches(&ip) {
+                return Err(ApiError::new(format!(
+                    "{} returned {} for {:?}",
+                    url, ip, family
+                )));
+            }
+            Ok(ip)
+        })
+    }
+}
+
+/// Tries each source in turn and returns the first address found.
+pub struct FallbackIpSource(pub Vec<Box<dyn IpSource>>);
+
+impl IpSource for FallbackIpSource {
+    fn public_ip(&self, family: IpFamily) -> IpFuture<'_> {
+        Box::pin(async move {
+            let mut errors = vec![];
+            for source in &self.0 {
+                match source.public_ip(family).await {
+                    Ok(ip) => return Ok(ip),
+                    Err(e) => errors.push(e.to_string()),
+                }
+            }
+            Err(ApiError::new(format!(
+                "no source found a {:?} address: {}",
+                family,
+                errors.join(", ")
+            )))
+        })
+    }
+}
+
+fn parse_ip_response(body: &str) -> Option<IpAddr> {
+    let body = body.trim();
+    if let Ok(ip) = IpAddr::from_str(body) {
+        return Some(ip);
+    }
+    body.lines()
+        .find_map(|l| l.strip_prefix("ip="))
+        .and_then(|ip| IpAddr::from_str(ip.trim()).ok())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DdnsStatus {
+    Unchanged(IpAddr),
+    Updated { from: Option<IpAddr>, to: IpAddr },
+    Created(IpAddr),
+}
+
+/// Outcome for each configured family, in configuration order. One family failing does not
+/// stop the others from being checked.
+#[derive(Debug)]
+pub struct DdnsReport {
+    pub results: Vec<(IpFamily, ApiResult<DdnsStatus>)>,
+}
+
+impl DdnsReport {
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|(_, r)| r.is_ok())
+    }
+
+    pub fn into_result(self) -> ApiResult<Vec<(IpFamily, DdnsStatus)>> {
+        let mut statuses = vec![];
+        let mut failed = vec![];
+        for (family, res) in self.results {
+            match res {
+                Ok(status) => statuses.push((family, status)),
+                Err(e) => failed.push(format!("{:?}: {}", family, e)),
+            }
+        }
+        if failed.is_empty() {
+            Ok(statuses)
+        } else {
+            Err(ApiError::new(failed.join("; ")))
+        }
+    }
+}
+
+/// Keeps A and/or AAAA records for one name pointed at this host's public address.
+pub struct DdnsUpdater {
+    c: Cloudflare,
+    zone_id: ZoneId,
+    name: String,
+    source: Box<dyn IpSource>,
+    families: Vec<IpFamily>,
+    create_missing: bool,
+    ttl: Option<usize>,
+    proxied: Option<bool>,
+    interval: Duration,
+    max_backoff: Duration,
+}
+
+impl DdnsUpdater {
+    pub fn new(c: &Cloudflare, zone_id: ZoneId, name: &str) -> Self {
+        Self {
+            c: c.clone(),
+            zone_id,
+            name: name.trim_end_matches('.').to_ascii_lowercase(),
+            source: Box::new(HttpIpSource::default()),
+            families: vec![IpFamily::V4],
+            create_missing: true,
+            ttl: None,
+            proxied: None,
+            interval: Duration::from_secs(300),
+            max_backoff: Duration::from_secs(3600),
+        }
+    }
+
+    pub fn with_source(mut self, source: impl IpSource + 'static) -> Self {
+        self.source = Box::new(source);
+        self
+    }
+
+    pub fn with_families(mut self, families: &[IpFamily]) -> Self {
+        self.families = families.to_vec();
+        self
+    }
+
+    /// Whether to create the record when the zone has none for this name. Defaults to `true`.
+    pub fn with_create_missing(mut self, create_missing: bool) -> Self {
+        self.create_missing = create_missing;
+        self
+    }
+
+    /// TTL and proxied flag for records the updater creates. Existing records keep theirs.
+    pub fn with_ttl(mut self, ttl: usize) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn with_proxied(mut self, proxied: bool) -> Self {
+        self.proxied = Some(proxied);
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Checks each configured family once, updating the record only when the address changed.
+    pub async fn update_once(&self) -> DdnsReport {
+        let mut results = vec![];
+        for family in &self.families {
+            let res = match self.source.public_ip(*family).await {
+                Ok(ip) => self.sync_family(*family, ip).await,
+                Err(e) => Err(e),
+            };
+            results.push((*family, res));
+        }
+        DdnsReport { results }
+    }
+
+    async fn sync_family(&self, family: IpFamily, ip: IpAddr) -> ApiResult<DdnsStatus> {
+        let existing = self.find_record(family).await?;
+        let dns = self.c.dns();
+        match existing {
+            Some(record) => {
+                let current = match &record {
+                    RecordMessage::A(r) => r.content.map(IpAddr::V4),
+                    RecordMessage::AAAA(r) => r.content.map(IpAddr::V6),
+                    _ => None,
+                };
+                if current == Some(ip) {
+                    return Ok(DdnsStatus::Unchanged(ip));
+                }
+                let id = record
+                    .id()
+                    .cloned()
+                    .ok_or_else(|| ApiError::new("record has no id"))?;
+                dns.update_record(&self.zone_id, &id, self.request(ip, false))
+                    .await?
+                    .into_result()?;
+                info!("updated {} {} to {}", family.record_type(), self.name, ip);
+                Ok(DdnsStatus::Updated {
+                    from: current,
+                    to: ip,
+                })
+            }
+            None if self.create_missing => {
+                dns.create_record(&self.zone_id, self.request(ip, true))
+                    .await?
+                    .into_result()?;
+                info!("created {} {} at {}", family.record_type(), self.name, ip);
+                Ok(DdnsStatus::Created(ip))
+            }
+            None => Err(ApiError::with_kind(
+                ApiErrorKind::NotFound,
+                format!("no {} record for {}", family.record_type(), self.name),
+            )),
+        }
+    }
+
+    fn request(&self, ip: IpAddr, create: bool) -> RecordRequest {
+        let (ttl, proxied) = match create {
+            true => (self.ttl, self.proxied),
+            false => (None, None),
+        };
+        match ip {
+            IpAddr::V4(ip) => RecordRequest::A(ARecordRequest {
+                name: Some(self.name.clone()),
+                content: Some(ip),
+                ttl,
+                proxied,
+                ..Default::default()
+            }),
+            IpAddr::V6(ip) => RecordRequest::AAAA(AAAARecordRequest {
+                name: Some(self.name.clone()),
+                content: Some(ip),
+                ttl,
+                proxied,
+                ..Default::default()
+            }),
+        }
+    }
+
+    async fn find_record(&self, family: IpFamily) -> ApiResult<Option<RecordMessage>> {
+        let res: V4PagePaginationArray<RecordMessage> = self
+            .c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dns_records", self.zone_id),
+                    params: Some(vec![
+                        ("name".to_string(), self.name.clone()),
+                        ("type".to_string(), family.record_type().to_string()),
+                    ]),
+                    body: None,
+                },
+            )
+            .await?;
+        Ok(res.into_result()?.into_iter().next())
+    }
+
+    /// Runs [`DdnsUpdater::update_once`] every interval until `shutdown` resolves. Failed
+    /// attempts are retried with exponential backoff, starting at five seconds and capped at
+    /// the configured maximum.
+    pub async fn run_until<F: Future<Output = ()>>(&self, shutdown: F) {
+        tokio::pin!(shutdown);
+        let mut backoff = None;
+        loop {
+            let wait = match self.update_once().await.into_result() {
+                Ok(_) => {
+                    backoff = None;
+                    self.interval
+                }
+                Err(e) => {
+                    let next = next_backoff(backoff, self.max_backoff);
+                    warn!(
+                        "ddns update for {} failed, retrying in {:?}:{}",
+                        self.name, next, e
+                    );
+                    backoff = Some(next);
+                    next
+                }
+            };
+            tokio::select! {
+                _ = &mut shutdown => return,
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+    }
+
+    pub async fn run(&self) {
+        self.run_until(std::future::pending()).await
+    }
+}
+
+fn next_backoff(current: Option<Duration>, max: Duration) -> Duration {
+    match current {
+        None => Duration::from_secs(5).min(max),
+        Some(d) => (d * 2).min(max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_and_trace_responses() {
+        assert_eq!(
+            parse_ip_response("203.0.113.7\n"),
+            Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)))
+        );
+        assert_eq!(
+            parse_ip_response("fl=1\nh=1.1.1.1\nip=2001:db8::7\nts=1\n"),
+            Some(IpAddr::V6("2001:db8::7".parse().unwrap()))
+        );
+        assert_eq!(parse_ip_response("<html>"), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let max = Duration::from_secs(30);
+        let mut b = None;
+        let mut seen = vec![];
+        for _ in 0..5 {
+            let next = next_backoff(b, max);
+            seen.push(next.as_secs());
+            b = Some(next);
+        }
+        assert_eq!(seen, vec![5, 10, 20, 30, 30]);
+    }
+
+    #[tokio::test]
+    async fn fallback_uses_first_working_source() {
+        let source = FallbackIpSource(vec![
+            Box::new(FixedIpSource::default()),
+            Box::new(FixedIpSource {
+                v4: Some(Ipv4Addr::new(198, 51, 100, 1)),
+                v6: None,
+            }),
+        ]);
+        assert_eq!(
+            source.public_ip(IpFamily::V4).await.unwrap(),
+            IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1))
+        );
+        assert!(source.public_ip(IpFamily::V6).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn http_source_times_out_on_a_silent_server() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let _hold = tokio::spawn(async move {
+            let (_conn, _) = listener.accept().await.unwrap();
+            std::future::pending::<()>().await
+        });
+        let source = HttpIpSource::new()
+            .with_url(IpFamily::V4, &url)
+            .with_timeout(Duration::from_millis(50));
+        let err = source.public_ip(IpFamily::V4).await.unwrap_err();
+        assert_eq!(err.kind, ApiErrorKind::Transport);
+        assert!(err.to_string().contains("timed out"), "{}", err);
+    }
+}
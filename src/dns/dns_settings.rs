@@ -1,9 +1,248 @@
+use hyper::Method;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+use crate::{AccountId, ApiResponse, ApiResult, Cloudflare, PerformOptions, ZoneId};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct DnsSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ipv4_only: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ipv6_only: Option<bool>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameserverType {
+    #[serde(rename = "cloudflare.standard")]
+    CloudflareStandard,
+    #[serde(rename = "cloudflare.standard.random")]
+    CloudflareRandom,
+    #[serde(rename = "custom.account")]
+    CustomAccount,
+    #[serde(rename = "custom.tenant")]
+    CustomTenant,
+    #[serde(rename = "custom.zone")]
+    CustomZone,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DnsSettingsNameservers {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub ns_type: Option<NameserverType>,
+    /// Which custom nameserver set to use, for the `custom.*` types.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ns_set: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DnsSettingsSoa {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expire: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_ttl: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DnsSettingsInternalDns {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_zone_id: Option<ZoneId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneMode {
+    Standard,
+    CdnOnly,
+    DnsOnly,
+}
+
+/// Zone-wide DNS settings. Every field is optional, so the same struct serves as the body of
+/// a partial update.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ZoneDnsSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flatten_all_cnames: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foundation_dns: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal_dns: Option<DnsSettingsInternalDns>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multi_provider: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nameservers: Option<DnsSettingsNameservers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ns_ttl: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary_overrides: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soa: Option<DnsSettingsSoa>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zone_mode: Option<ZoneMode>,
+}
+
+/// Account-wide DNS settings, applied to zones created in the account from then on.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AccountDnsSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zone_defaults: Option<ZoneDnsSettings>,
+}
+
+pub struct CloudflareDnsSettings {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareDnsSettings {
+    pub async fn get_zone_settings(
+        &self,
+        zone_id: &ZoneId,
+    ) -> ApiResult<ApiResponse<ZoneDnsSettings>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dns_settings", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    /// Changes only the fields set on `settings`.
+    pub async fn update_zone_settings(
+        &self,
+        zone_id: &ZoneId,
+        settings: ZoneDnsSettings,
+    ) -> ApiResult<ApiResponse<ZoneDnsSettings>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::PATCH,
+                PerformOptions {
+                    path: format!("zones/{}/dns_settings", zone_id),
+                    params: None,
+                    body: Some(settings),
+                },
+            )
+            .await
+    }
+
+    pub async fn get_account_settings(
+        &self,
+        account_id: &AccountId,
+    ) -> ApiResult<ApiResponse<AccountDnsSettings>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("accounts/{}/dns_settings", account_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    /// Changes only the fields set on `settings`.
+    pub async fn update_account_settings(
+        &self,
+        account_id: &AccountId,
+        settings: AccountDnsSettings,
+    ) -> ApiResult<ApiResponse<AccountDnsSettings>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::PATCH,
+                PerformOptions {
+                    path: format!("accounts/{}/dns_settings", account_id),
+                    params: None,
+                    body: Some(settings),
+                },
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_settings_round_trip() {
+        let raw = serde_json::json!({
+            "flatten_all_cnames": false,
+            "foundation_dns": false,
+            "internal_dns": {"reference_zone_id": "023e105f4ecef8ad9ca31a8372d0c353"},
+            "multi_provider": false,
+            "nameservers": {"type": "custom.account", "ns_set": 2},
+            "ns_ttl": 86400,
+            "secondary_overrides": false,
+            "soa": {
+                "expire": 604800,
+                "min_ttl": 1800,
+                "mname": "kristina.ns.cloudflare.com",
+                "refresh": 10000,
+                "retry": 2400,
+                "rname": "admin.example.com",
+                "ttl": 3600
+            },
+            "zone_mode": "dns_only"
+        });
+        let settings: ZoneDnsSettings = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(settings.zone_mode, Some(ZoneMode::DnsOnly));
+        assert_eq!(
+            settings.nameservers.as_ref().unwrap().ns_type,
+            Some(NameserverType::CustomAccount)
+        );
+        assert_eq!(serde_json::to_value(&settings).unwrap(), raw);
+
+        let account = AccountDnsSettings {
+            zone_defaults: Some(ZoneDnsSettings {
+                ns_ttl: Some(300),
+                ..Default::default()
+            }),
+        };
+        let value = serde_json::to_value(&account).unwrap();
+        assert_eq!(value, serde_json::json!({"zone_defaults": {"ns_ttl": 300}}));
+        assert_eq!(
+            serde_json::from_value::<AccountDnsSettings>(value).unwrap(),
+            account
+        );
+        assert_eq!(
+            serde_json::to_value(ZoneDnsSettings::default()).unwrap(),
+            serde_json::json!({})
+        );
+    }
+
+    #[test]
+    fn nameserver_types_use_dotted_names() {
+        for (ns_type, wire) in [
+            (NameserverType::CloudflareStandard, "cloudflare.standard"),
+            (
+                NameserverType::CloudflareRandom,
+                "cloudflare.standard.random",
+            ),
+            (NameserverType::CustomAccount, "custom.account"),
+            (NameserverType::CustomTenant, "custom.tenant"),
+            (NameserverType::CustomZone, "custom.zone"),
+        ] {
+            assert_eq!(serde_json::to_value(ns_type).unwrap(), wire);
+            assert_eq!(
+                serde_json::from_value::<NameserverType>(wire.into()).unwrap(),
+                ns_type
+            );
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::{fmt::Display, str::FromStr};
+
+use hyper::Method;
+use serde::{Deserialize, Serialize};
+
+use super::ContentError;
+use crate::{ApiResponse, ApiResult, Cloudflare, PerformOptions, Timestamp, ZoneId};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DnssecStatus {
+    Active,
+    Pending,
+    Disabled,
+    PendingDisabled,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Dnssec {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<DnssecStatus>,
+    /// DNSSEC algorithm number, e.g. `"13"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest_algorithm: Option<String>,
+    /// Digest type number, e.g. `"2"` for SHA-256.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest_type: Option<String>,
+    /// The full DS record, as a zone file line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ds: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_tag: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnssec_multi_signer: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnssec_presigned: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnssec_use_nsec3: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+}
+
+impl Dnssec {
+    /// The DS record to give the registrar, built from the typed fields or, failing that,
+    /// parsed from `ds`. `None` until Cloudflare has generated the signing key.
+    pub fn ds_record(&self) -> Option<DsRecord> {
+        let from_fields = || {
+            Some(DsRecord {
+                key_tag: self.key_tag?,
+                algorithm: self.algorithm.as_ref()?.parse().ok()?,
+                digest_type: self.digest_type.as_ref()?.parse().ok()?,
+                digest: self.digest.as_ref()?.to_ascii_uppercase(),
+            })
+        };
+        from_fields().or_else(|| self.ds.as_ref()?.parse().ok())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EditDnssecRequest {
+    /// Only `Active` and `Disabled` may be set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<DnssecStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnssec_multi_signer: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnssec_presigned: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnssec_use_nsec3: Option<bool>,
+}
+
+/// Delegation signer record, the form registrars ask for.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DsRecord {
+    pub key_tag: u16,
+    pub algorithm: u8,
+    pub digest_type: u8,
+    pub digest: String,
+}
+
+impl DsRecord {
+    /// The record as a zone file line for `zone`.
+    pub fn to_zone_line(&self, zone: &str, ttl: usize) -> String {
+        format!("{}.\t{}\tIN\tDS\t{}", zone.trim_end_matches('.'), ttl, self)
+    }
+}
+
+impl Display for DsRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.key_tag, self.algorithm, self.digest_type, self.digest
+        )
+    }
+}
+
+/// Accepts either the bare rdata (`2371 13 2 1F98...`) or a full zone file line as found in
+/// [`Dnssec::ds`].
+impl FromStr for DsRecord {
+    type Err = ContentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let rdata = match fields.iter().position(|f| f.eq_ignore_ascii_case("DS")) {
+            Some(i) => &fields[i + 1..],
+            None => &fields[..],
+        };
+        let invalid = || ContentError(format!("invalid DS record: {}", s));
+        let [key_tag, algorithm, digest_type, digest @ ..] = rdata else {
+            return Err(invalid());
+        };
+        let digest = digest.concat();
+        if digest.is_empty() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            key_tag: key_tag.parse().map_err(|_| invalid())?,
+            algorithm: algorithm.parse().map_err(|_| invalid())?,
+            digest_type: digest_type.parse().map_err(|_| invalid())?,
+            digest: digest.to_ascii_uppercase(),
+        })
+    }
+}
+
+pub struct CloudflareDnssec {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareDnssec {
+    pub async fn get(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Dnssec>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dnssec", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn edit(
+        &self,
+        zone_id: &ZoneId,
+        req: EditDnssecRequest,
+    ) -> ApiResult<ApiResponse<Dnssec>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::PATCH,
+                PerformOptions {
+                    path: format!("zones/{}/dnssec", zone_id),
+                    params: None,
+                    body: Some(req),
+                },
+            )
+            .await
+    }
+
+    /// Starts signing the zone. The status stays `Pending` until the DS record is published
+    /// at the registrar.
+    pub async fn enable(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Dnssec>> {
+        self.edit(
+            zone_id,
+            EditDnssecRequest {
+                status: Some(DnssecStatus::Active),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Stops signing the zone. Remove the DS record at the registrar first.
+    pub async fn disable(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Dnssec>> {
+        self.edit(
+            zone_id,
+            EditDnssecRequest {
+                status: Some(DnssecStatus::Disabled),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    pub async fn set_multi_signer(
+        &self,
+        zone_id: &ZoneId,
+        enabled: bool,
+    ) -> ApiResult<ApiResponse<Dnssec>> {
+        self.edit(
+            zone_id,
+            EditDnssecRequest {
+                dnssec_multi_signer: Some(enabled),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Serves signatures transferred from the primary as-is. Only meaningful for secondary
+    /// zones.
+    pub async fn set_presigned(
+        &self,
+        zone_id: &ZoneId,
+        enabled: bool,
+    ) -> ApiResult<ApiResponse<Dnssec>> {
+        self.edit(
+            zone_id,
+            EditDnssecRequest {
+                dnssec_presigned: Some(enabled),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Removes the zone's DNSSEC keys and records.
+    pub async fn delete(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<String>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::DELETE,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dnssec", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    /// Fetches the zone's DNSSEC details and returns its DS record.
+    pub async fn ds_record(&self, zone_id: &ZoneId) -> ApiResult<Option<DsRecord>> {
+        Ok(self.get(zone_id).await?.into_result()?.ds_record())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "48E939042E82C22542CB377B580DFDC52A361CEFDC72E7F9107E2B6BD9306A45";
+
+    #[test]
+    fn parses_ds_lines() {
+        let line = format!("example.com. 3600 IN DS 16953 13 2 {}", DIGEST);
+        let ds: DsRecord = line.parse().unwrap();
+        assert_eq!((ds.key_tag, ds.algorithm, ds.digest_type), (16953, 13, 2));
+        assert_eq!(ds.digest, DIGEST);
+        assert_eq!(
+            ds.to_zone_line("example.com", 3600),
+            format!("example.com.\t3600\tIN\tDS\t16953 13 2 {}", DIGEST)
+        );
+
+        let split = format!(
+            "16953 13 2 {} {}",
+            &DIGEST[..32],
+            &DIGEST[32..].to_lowercase()
+        );
+        assert_eq!(split.parse::<DsRecord>().unwrap(), ds);
+        assert!("16953 13 2".parse::<DsRecord>().is_err());
+        assert!("16953 13 2 XYZ".parse::<DsRecord>().is_err());
+    }
+
+    #[test]
+    fn builds_ds_from_fields() {
+        let dnssec = Dnssec {
+            algorithm: Some("13".to_string()),
+            digest_type: Some("2".to_string()),
+            digest: Some(DIGEST.to_lowercase()),
+            key_tag: Some(16953),
+            ..Default::default()
+        };
+        assert_eq!(
+            dnssec.ds_record().unwrap().to_string(),
+            format!("16953 13 2 {}", DIGEST)
+        );
+
+        let only_ds = Dnssec {
+            ds: Some(format!("example.com. 3600 IN DS 16953 13 2 {}", DIGEST)),
+            ..Default::default()
+        };
+        assert_eq!(only_ds.ds_record(), dnssec.ds_record());
+        assert_eq!(Dnssec::default().ds_record(), None);
+    }
+}
@@ -0,0 +1,266 @@
+/// How a text filter compares against a record field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextMatch {
+    Exact(String),
+    Contains(String),
+    StartsWith(String),
+    EndsWith(String),
+}
+
+impl TextMatch {
+    fn param(&self, field: &str) -> (String, String) {
+        let (op, value) = match self {
+            Self::Exact(v) => ("exact", v),
+            Self::Contains(v) => ("contains", v),
+            Self::StartsWith(v) => ("startswith", v),
+            Self::EndsWith(v) => ("endswith", v),
+        };
+        (format!("{}.{}", field, op), value.clone())
+    }
+}
+
+/// Filters on record tags, which take the form `name:value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagFilter {
+    /// A tag with this name, whatever its value.
+    Present(String),
+    /// No tag with this name.
+    Absent(String),
+    Exact(String, String),
+    /// A tag with this name whose value contains the given text.
+    Contains(String, String),
+    StartsWith(String, String),
+    EndsWith(String, String),
+}
+
+impl TagFilter {
+    /// `Exact` for `name:value`, `Present` for a bare `name`.
+    pub fn from_tag(tag: &str) -> Self {
+        match tag.split_once(':') {
+            Some((name, value)) => Self::Exact(name.to_string(), value.to_string()),
+            None => Self::Present(tag.to_string()),
+        }
+    }
+
+    fn param(&self) -> (String, String) {
+        let (op, value) = match self {
+            Self::Present(name) => ("present", name.clone()),
+            Self::Absent(name) => ("absent", name.clone()),
+            Self::Exact(name, value) => ("exact", format!("{}:{}", name, value)),
+            Self::Contains(name, value) => ("contains", format!("{}:{}", name, value)),
+            Self::StartsWith(name, value) => ("startswith", format!("{}:{}", name, value)),
+            Self::EndsWith(name, value) => ("endswith", format!("{}:{}", name, value)),
+        };
+        (format!("tag.{}", op), value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchMode {
+    #[default]
+    All,
+    Any,
+}
+
+impl MatchMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Any => "any",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordOrder {
+    Type,
+    Name,
+    Content,
+    Ttl,
+    Proxied,
+}
+
+impl RecordOrder {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Type => "type",
+            Self::Name => "name",
+            Self::Content => "content",
+            Self::Ttl => "ttl",
+            Self::Proxied => "proxied",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Query filters for [`crate::CloudflareDns::list_records`]. Tag filters add up, so several
+/// can be combined with [`RecordFilter::with_tag_match`]; setting any other filter again
+/// replaces its previous value, whatever operator either one used.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordFilter {
+    params: Vec<(String, String)>,
+}
+
+impl RecordFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_type(self, record_type: &str) -> Self {
+        self.set("type", record_type.to_ascii_uppercase())
+    }
+
+    pub fn with_name(self, m: TextMatch) -> Self {
+        let (k, v) = m.param("name");
+        self.set(&k, v)
+    }
+
+    pub fn with_content(self, m: TextMatch) -> Self {
+        let (k, v) = m.param("content");
+        self.set(&k, v)
+    }
+
+    pub fn with_comment(self, m: TextMatch) -> Self {
+        let (k, v) = m.param("comment");
+        self.set(&k, v)
+    }
+
+    /// Only records that have (`true`) or lack (`false`) a comment.
+    pub fn with_comment_present(self, present: bool) -> Self {
+        let key = if present {
+            "comment.present"
+        } else {
+            "comment.absent"
+        };
+        self.set(key, String::new())
+    }
+
+    pub fn with_tag(mut self, tag: TagFilter) -> Self {
+        let param = tag.param();
+        if !self.params.contains(&param) {
+            self.params.push(param);
+        }
+        self
+    }
+
+    /// Combines the tag filters with each other. Defaults to `All`.
+    pub fn with_tag_match(self, mode: MatchMode) -> Self {
+        self.set("tag_match", mode.as_str().to_string())
+    }
+
+    /// Combines the other filters, with the tag filters counting as one. Defaults to `All`.
+    pub fn with_match(self, mode: MatchMode) -> Self {
+        self.set("match", mode.as_str().to_string())
+    }
+
+    pub fn with_proxied(self, proxied: bool) -> Self {
+        self.set("proxied", proxied.to_string())
+    }
+
+    /// Free-text search across name, content and comment.
+    pub fn with_search(self, search: &str) -> Self {
+        self.set("search", search.to_string())
+    }
+
+    pub fn with_order(self, order: RecordOrder, direction: SortDirection) -> Self {
+        let direction = match direction {
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        };
+        self.set("order", order.as_str().to_string())
+            .set("direction", direction.to_string())
+    }
+
+    pub fn with_per_page(self, per_page: usize) -> Self {
+        self.set("per_page", per_page.to_string())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    pub(crate) fn params(&self) -> Vec<(String, String)> {
+        self.params.clone()
+    }
+
+    /// Replaces every filter on the field `key` belongs to, so `name.contains` drops an
+    /// earlier `name.exact`.
+    fn set(mut self, key: &str, value: String) -> Self {
+        let field = key.split_once('.').map_or(key, |(field, _)| field);
+        self.params
+            .retain(|(k, _)| k.split_once('.').map_or(k.as_str(), |(field, _)| field) != field);
+        self.params.push((key.to_string(), value));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_query_params() {
+        let params = RecordFilter::new()
+            .with_type("txt")
+            .with_name(TextMatch::EndsWith("example.com".to_string()))
+            .with_comment(TextMatch::StartsWith("managed".to_string()))
+            .with_tag(TagFilter::from_tag("owner:team-x"))
+            .with_tag(TagFilter::from_tag("env:prod"))
+            .with_tag(TagFilter::Absent("legacy".to_string()))
+            .with_tag_match(MatchMode::Any)
+            .with_order(RecordOrder::Content, SortDirection::Asc)
+            .with_order(RecordOrder::Name, SortDirection::Desc)
+            .params();
+        let expected: Vec<(String, String)> = [
+            ("type", "TXT"),
+            ("name.endswith", "example.com"),
+            ("comment.startswith", "managed"),
+            ("tag.exact", "owner:team-x"),
+            ("tag.exact", "env:prod"),
+            ("tag.absent", "legacy"),
+            ("tag_match", "any"),
+            ("order", "name"),
+            ("direction", "desc"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        assert_eq!(params, expected);
+        assert_eq!(
+            TagFilter::from_tag("owner"),
+            TagFilter::Present("owner".to_string())
+        );
+    }
+
+    #[test]
+    fn resetting_a_filter_replaces_its_operator() {
+        let params = RecordFilter::new()
+            .with_name(TextMatch::Exact("www.example.com".to_string()))
+            .with_name(TextMatch::Contains("example".to_string()))
+            .with_comment_present(true)
+            .with_comment_present(false)
+            .with_tag(TagFilter::Present("owner".to_string()))
+            .with_tag_match(MatchMode::Any)
+            .params();
+        assert_eq!(
+            params,
+            vec![
+                ("name.contains".to_string(), "example".to_string()),
+                ("comment.absent".to_string(), String::new()),
+                ("tag.present".to_string(), "owner".to_string()),
+                ("tag_match".to_string(), "any".to_string()),
+            ]
+        );
+        let params = RecordFilter::new()
+            .with_comment(TextMatch::StartsWith("managed".to_string()))
+            .with_comment_present(false)
+            .params();
+        assert_eq!(params, vec![("comment.absent".to_string(), String::new())]);
+    }
+}
@@ -1,16 +1,51 @@
+pub mod analytics;
+pub mod batch;
+pub mod bulk;
+pub mod ddns;
 pub mod dns_settings;
+pub mod dnssec;
+pub mod filter;
+pub mod reconcile;
 pub mod record;
-pub use record::{AAAARecord, ARecord, CNAMERecord, MXRecord, TXTRecord};
+pub mod scan;
+pub mod zone_file;
+pub use analytics::{
+    AnalyticsField, AnalyticsFilter, CloudflareDnsAnalytics, DnsAnalyticsByTime,
+    DnsAnalyticsDimension, DnsAnalyticsMetric, DnsAnalyticsQuery, DnsAnalyticsQueryInfo,
+    DnsAnalyticsReport, DnsAnalyticsRow, DnsAnalyticsTimeRow, TimeDelta,
+};
+pub use batch::{BatchBuilder, BatchDelete, BatchRequest, BatchResult, BatchUpdate};
+pub use bulk::{BulkExecutor, BulkOp, BulkOutcome, BulkReport};
+pub use ddns::{
+    DdnsReport, DdnsStatus, DdnsUpdater, FallbackIpSource, FixedIpSource, HttpIpSource, IpFamily,
+    IpSource,
+};
+pub use dns_settings::{
+    AccountDnsSettings, CloudflareDnsSettings, DnsSettings, DnsSettingsInternalDns,
+    DnsSettingsNameservers, DnsSettingsSoa, NameserverType, ZoneDnsSettings, ZoneMode,
+};
+pub use dnssec::{CloudflareDnssec, Dnssec, DnssecStatus, DsRecord, EditDnssecRequest};
+pub use filter::{MatchMode, RecordFilter, RecordOrder, SortDirection, TagFilter, TextMatch};
+pub use reconcile::{compute_plan, ApplyReport, Change, Ownership, Plan, Reconciler};
+pub use record::{
+    AAAARecord, AAAARecordRequest, ARecord, ARecordRequest, CNAMERecord, CNAMERecordRequest,
+    MXRecord, MXRecordRequest, OtherRecord, OtherRecordRequest, TXTRecord, TXTRecordRequest,
+};
+pub use scan::{CloudflareDnsScan, ScanReviewRequest, ScanReviewResult};
 use std::{
     fmt::Display,
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
     str::FromStr,
 };
+pub use zone_file::{parse_zone_file, to_zone_file, ImportRecordsResult, ParsedZoneFile};
 
+use crate::{RecordId, Timestamp, ZoneId};
 use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-pub struct ContentError(String);
+#[derive(Debug)]
+pub struct ContentError(pub(crate) String);
 
 impl Display for ContentError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -23,8 +58,10 @@ pub trait DnsIpContent {
     fn set_content(&mut self, addr: IpAddr) -> Result<(), ContentError>;
 }
 
+/// A record as the API returns it. Types without their own variant decode as
+/// [`RecordMessage::Other`], so listing a zone with NS, SRV or CAA records still works.
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(tag = "type")]
+#[serde(try_from = "Value", into = "RawRecord<KnownRecord, OtherRecord>")]
 #[enum_dispatch]
 pub enum RecordMessage {
     A(ARecord),
@@ -32,14 +69,225 @@ pub enum RecordMessage {
     CNAME(CNAMERecord),
     MX(MXRecord),
     TXT(TXTRecord),
+    Other(OtherRecord),
+}
+
+/// Request body for creating or updating a record.
+///
+/// Unlike [`RecordMessage`], which mirrors what the API returns, a `RecordRequest` only carries
+/// writable fields, so server-managed fields such as `id`, `zone_id`, `meta` or the
+/// `*_modified_on` timestamps never end up in a POST, PUT or PATCH body.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(
+    try_from = "Value",
+    into = "RawRecord<KnownRecordRequest, OtherRecordRequest>"
+)]
+pub enum RecordRequest {
+    A(ARecordRequest),
+    AAAA(AAAARecordRequest),
+    CNAME(CNAMERecordRequest),
+    MX(MXRecordRequest),
+    TXT(TXTRecordRequest),
+    Other(OtherRecordRequest),
+}
+
+const KNOWN_TYPES: [&str; 5] = ["A", "AAAA", "CNAME", "MX", "TXT"];
+
+/// The wire form of [`RecordMessage`] and [`RecordRequest`]: the known types are tagged by
+/// `type`, and the fallback carries its own `type` field.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum RawRecord<K, O> {
+    Known(K),
+    Other(O),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[allow(clippy::upper_case_acronyms)]
+enum KnownRecord {
+    A(ARecord),
+    AAAA(AAAARecord),
+    CNAME(CNAMERecord),
+    MX(MXRecord),
+    TXT(TXTRecord),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[allow(clippy::upper_case_acronyms)]
+enum KnownRecordRequest {
+    A(ARecordRequest),
+    AAAA(AAAARecordRequest),
+    CNAME(CNAMERecordRequest),
+    MX(MXRecordRequest),
+    TXT(TXTRecordRequest),
+}
+
+fn is_known_type(v: &Value) -> bool {
+    v.get("type")
+        .and_then(Value::as_str)
+        .is_some_and(|t| KNOWN_TYPES.contains(&t))
+}
+
+impl TryFrom<Value> for RecordMessage {
+    type Error = serde_json::Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        if !is_known_type(&v) {
+            return serde_json::from_value(v).map(Self::Other);
+        }
+        Ok(match serde_json::from_value(v)? {
+            KnownRecord::A(r) => Self::A(r),
+            KnownRecord::AAAA(r) => Self::AAAA(r),
+            KnownRecord::CNAME(r) => Self::CNAME(r),
+            KnownRecord::MX(r) => Self::MX(r),
+            KnownRecord::TXT(r) => Self::TXT(r),
+        })
+    }
+}
+
+impl From<RecordMessage> for RawRecord<KnownRecord, OtherRecord> {
+    fn from(r: RecordMessage) -> Self {
+        match r {
+            RecordMessage::A(r) => Self::Known(KnownRecord::A(r)),
+            RecordMessage::AAAA(r) => Self::Known(KnownRecord::AAAA(r)),
+            RecordMessage::CNAME(r) => Self::Known(KnownRecord::CNAME(r)),
+            RecordMessage::MX(r) => Self::Known(KnownRecord::MX(r)),
+            RecordMessage::TXT(r) => Self::Known(KnownRecord::TXT(r)),
+            RecordMessage::Other(r) => Self::Other(r),
+        }
+    }
+}
+
+impl TryFrom<Value> for RecordRequest {
+    type Error = serde_json::Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        if !is_known_type(&v) {
+            return serde_json::from_value(v).map(Self::Other);
+        }
+        Ok(match serde_json::from_value(v)? {
+            KnownRecordRequest::A(r) => Self::A(r),
+            KnownRecordRequest::AAAA(r) => Self::AAAA(r),
+            KnownRecordRequest::CNAME(r) => Self::CNAME(r),
+            KnownRecordRequest::MX(r) => Self::MX(r),
+            KnownRecordRequest::TXT(r) => Self::TXT(r),
+        })
+    }
+}
+
+impl From<RecordRequest> for RawRecord<KnownRecordRequest, OtherRecordRequest> {
+    fn from(r: RecordRequest) -> Self {
+        match r {
+            RecordRequest::A(r) => Self::Known(KnownRecordRequest::A(r)),
+            RecordRequest::AAAA(r) => Self::Known(KnownRecordRequest::AAAA(r)),
+            RecordRequest::CNAME(r) => Self::Known(KnownRecordRequest::CNAME(r)),
+            RecordRequest::MX(r) => Self::Known(KnownRecordRequest::MX(r)),
+            RecordRequest::TXT(r) => Self::Known(KnownRecordRequest::TXT(r)),
+            RecordRequest::Other(r) => Self::Other(r),
+        }
+    }
+}
+
+impl From<RecordMessage> for RecordRequest {
+    fn from(r: RecordMessage) -> Self {
+        match r {
+            RecordMessage::A(r) => RecordRequest::A(r.into()),
+            RecordMessage::AAAA(r) => RecordRequest::AAAA(r.into()),
+            RecordMessage::CNAME(r) => RecordRequest::CNAME(r.into()),
+            RecordMessage::MX(r) => RecordRequest::MX(r.into()),
+            RecordMessage::TXT(r) => RecordRequest::TXT(r.into()),
+            RecordMessage::Other(r) => RecordRequest::Other(r.into()),
+        }
+    }
+}
+
+impl RecordRequest {
+    pub fn record_type(&self) -> &str {
+        match self {
+            Self::A(_) => "A",
+            Self::AAAA(_) => "AAAA",
+            Self::CNAME(_) => "CNAME",
+            Self::MX(_) => "MX",
+            Self::TXT(_) => "TXT",
+            Self::Other(r) => &r.record_type,
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::A(r) => r.name.as_deref(),
+            Self::AAAA(r) => r.name.as_deref(),
+            Self::CNAME(r) => r.name.as_deref(),
+            Self::MX(r) => r.name.as_deref(),
+            Self::TXT(r) => r.name.as_deref(),
+            Self::Other(r) => r.name.as_deref(),
+        }
+    }
+
+    pub fn content(&self) -> Option<String> {
+        match self {
+            Self::A(r) => r.content.map(|c| c.to_string()),
+            Self::AAAA(r) => r.content.map(|c| c.to_string()),
+            Self::CNAME(r) => r.content.clone(),
+            Self::MX(r) => r.content.clone(),
+            Self::TXT(r) => r.content.clone(),
+            Self::Other(r) => r.content.clone(),
+        }
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        match self {
+            Self::A(r) => r.comment.as_deref(),
+            Self::AAAA(r) => r.comment.as_deref(),
+            Self::CNAME(r) => r.comment.as_deref(),
+            Self::MX(r) => r.comment.as_deref(),
+            Self::TXT(r) => r.comment.as_deref(),
+            Self::Other(r) => r.comment.as_deref(),
+        }
+    }
+
+    pub fn set_comment(&mut self, v: Option<String>) {
+        match self {
+            Self::A(r) => r.comment = v,
+            Self::AAAA(r) => r.comment = v,
+            Self::CNAME(r) => r.comment = v,
+            Self::MX(r) => r.comment = v,
+            Self::TXT(r) => r.comment = v,
+            Self::Other(r) => r.comment = v,
+        }
+    }
+
+    pub fn tags(&self) -> Option<&Vec<String>> {
+        match self {
+            Self::A(r) => r.tags.as_ref(),
+            Self::AAAA(r) => r.tags.as_ref(),
+            Self::CNAME(r) => r.tags.as_ref(),
+            Self::MX(r) => r.tags.as_ref(),
+            Self::TXT(r) => r.tags.as_ref(),
+            Self::Other(r) => r.tags.as_ref(),
+        }
+    }
+
+    pub fn set_tags(&mut self, v: Option<Vec<String>>) {
+        match self {
+            Self::A(r) => r.tags = v,
+            Self::AAAA(r) => r.tags = v,
+            Self::CNAME(r) => r.tags = v,
+            Self::MX(r) => r.tags = v,
+            Self::TXT(r) => r.tags = v,
+            Self::Other(r) => r.tags = v,
+        }
+    }
 }
 
 #[enum_dispatch(RecordMessage)]
 pub trait DnsCommon {
-    fn get_id(self) -> Option<String>;
-    fn set_id(&mut self, v: Option<String>);
-    fn get_zone_id(self) -> Option<String>;
-    fn set_zone_id(&mut self, v: Option<String>);
+    fn get_id(self) -> Option<RecordId>;
+    fn set_id(&mut self, v: Option<RecordId>);
+    fn get_zone_id(self) -> Option<ZoneId>;
+    fn set_zone_id(&mut self, v: Option<ZoneId>);
     fn get_zone_name(self) -> Option<String>;
     fn set_zone_name(&mut self, v: Option<String>);
     fn get_name(self) -> Option<String>;
@@ -64,51 +312,75 @@ impl ToRecordMessage for RecordMessage {
 }
 
 impl RecordMessage {
-    pub fn for_update(self) -> Self {
+    pub fn record_type(&self) -> &str {
         match self {
-            RecordMessage::A(r) => {
-                let mut rm = r.clone();
-                //rm.id = None;
-                //rm.zone_id = None;
-                rm.zone_name = None;
-                rm.meta = None;
-                rm.modified_on = None;
-                RecordMessage::A(rm)
-            }
-            RecordMessage::AAAA(r) => {
-                let mut rm = r.clone();
-                //rm.id = None;
-                //rm.zone_id = None;
-                rm.zone_name = None;
-                rm.meta = None;
-                RecordMessage::AAAA(rm)
-            }
-            RecordMessage::CNAME(r) => {
-                let mut rm = r.clone();
-                rm.id = None;
-                rm.zone_id = None;
-                rm.zone_name = None;
-                rm.meta = None;
-                RecordMessage::CNAME(rm)
-            }
-            RecordMessage::MX(r) => {
-                let mut rm = r.clone();
-                rm.id = None;
-                rm.zone_id = None;
-                rm.zone_name = None;
-                rm.meta = None;
-                RecordMessage::MX(rm)
-            }
-            RecordMessage::TXT(r) => {
-                let mut rm = r.clone();
-                rm.id = None;
-                rm.zone_id = None;
-                rm.zone_name = None;
-                rm.meta = None;
-                RecordMessage::TXT(rm)
-            }
+            Self::A(_) => "A",
+            Self::AAAA(_) => "AAAA",
+            Self::CNAME(_) => "CNAME",
+            Self::MX(_) => "MX",
+            Self::TXT(_) => "TXT",
+            Self::Other(r) => &r.record_type,
+        }
+    }
+
+    pub fn id(&self) -> Option<&RecordId> {
+        match self {
+            Self::A(r) => r.id.as_ref(),
+            Self::AAAA(r) => r.id.as_ref(),
+            Self::CNAME(r) => r.id.as_ref(),
+            Self::MX(r) => r.id.as_ref(),
+            Self::TXT(r) => r.id.as_ref(),
+            Self::Other(r) => r.id.as_ref(),
+        }
+    }
+
+    pub fn tags(&self) -> Option<&Vec<String>> {
+        match self {
+            Self::A(r) => r.tags.as_ref(),
+            Self::AAAA(r) => r.tags.as_ref(),
+            Self::CNAME(r) => r.tags.as_ref(),
+            Self::MX(r) => r.tags.as_ref(),
+            Self::TXT(r) => r.tags.as_ref(),
+            Self::Other(r) => r.tags.as_ref(),
+        }
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        match self {
+            Self::A(r) => r.comment.as_deref(),
+            Self::AAAA(r) => r.comment.as_deref(),
+            Self::CNAME(r) => r.comment.as_deref(),
+            Self::MX(r) => r.comment.as_deref(),
+            Self::TXT(r) => r.comment.as_deref(),
+            Self::Other(r) => r.comment.as_deref(),
         }
     }
+
+    pub fn created_on(&self) -> Option<&Timestamp> {
+        match self {
+            Self::A(r) => r.created_on.as_ref(),
+            Self::AAAA(r) => r.created_on.as_ref(),
+            Self::CNAME(r) => r.created_on.as_ref(),
+            Self::MX(r) => r.created_on.as_ref(),
+            Self::TXT(r) => r.created_on.as_ref(),
+            Self::Other(r) => r.created_on.as_ref(),
+        }
+    }
+
+    pub fn modified_on(&self) -> Option<&Timestamp> {
+        match self {
+            Self::A(r) => r.modified_on.as_ref(),
+            Self::AAAA(r) => r.modified_on.as_ref(),
+            Self::CNAME(r) => r.modified_on.as_ref(),
+            Self::MX(r) => r.modified_on.as_ref(),
+            Self::TXT(r) => r.modified_on.as_ref(),
+            Self::Other(r) => r.modified_on.as_ref(),
+        }
+    }
+
+    pub fn for_update(self) -> RecordRequest {
+        self.into()
+    }
     pub fn set_content(&mut self, value: String) {
         match self {
             Self::A(record) => {
@@ -125,3 +397,63 @@ impl RecordMessage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn other_record_requests_drop_read_only_fields() {
+        let record: RecordMessage = serde_json::from_value(serde_json::json!({
+            "type": "URI", "id": "372e67954025e0ba6aaa6d586b9e0b59",
+            "zone_id": "023e105f4ecef8ad9ca31a8372d0c353", "zone_name": "example.com",
+            "name": "_ftp._tcp.example.com", "content": "10 1 \"ftp://example.com\"",
+            "priority": 10, "data": {"weight": 1, "target": "ftp://example.com"},
+            "proxiable": false, "locked": false, "meta": {"auto_added": false},
+            "created_on": "2024-01-01T00:00:00Z", "modified_on": "2024-01-02T00:00:00Z",
+            "comment_modified_on": "2024-01-02T00:00:00Z",
+            "tags_modified_on": "2024-01-02T00:00:00Z"
+        }))
+        .unwrap();
+        let body = serde_json::to_value(RecordRequest::from(record)).unwrap();
+        let mut keys: Vec<&str> = body
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str())
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec!["content", "data", "name", "priority", "type"]);
+        assert_eq!(body["priority"], 10);
+    }
+
+    #[test]
+    fn decodes_a_mixed_zone() {
+        let records: Vec<RecordMessage> = serde_json::from_value(serde_json::json!([
+            {"type": "A", "name": "example.com", "content": "192.0.2.1", "ttl": 300},
+            {"type": "NS", "name": "sub.example.com", "content": "ns1.example.net"},
+            {"type": "CAA", "name": "example.com", "content": "0 issue \"letsencrypt.org\"",
+             "data": {"flags": 0, "tag": "issue", "value": "letsencrypt.org"}},
+            {"type": "MX", "name": "example.com", "content": "mail.example.com", "priority": 10}
+        ]))
+        .unwrap();
+        let types: Vec<&str> = records.iter().map(|r| r.record_type()).collect();
+        assert_eq!(types, vec!["A", "NS", "CAA", "MX"]);
+
+        let caa = &records[2];
+        let value = serde_json::to_value(caa).unwrap();
+        assert_eq!(value["type"], "CAA");
+        assert_eq!(value["data"]["tag"], "issue");
+        let req = RecordRequest::from(caa.clone());
+        assert_eq!(req.record_type(), "CAA");
+        let body = serde_json::to_value(&req).unwrap();
+        assert_eq!(body["data"]["value"], "letsencrypt.org");
+        assert_eq!(serde_json::from_value::<RecordRequest>(body).unwrap(), req);
+
+        // A known type with bad content is still an error, not an `Other`.
+        assert!(serde_json::from_value::<RecordMessage>(serde_json::json!({
+            "type": "A", "name": "example.com", "content": "not-an-ip"
+        }))
+        .is_err());
+    }
+}
@@ -0,0 +1,453 @@
+use std::fmt::Display;
+
+use log::info;
+use serde_json::Value;
+
+use super::{RecordMessage, RecordRequest};
+use crate::{ApiError, ApiResult, Cloudflare, RecordId, ZoneId};
+
+/// Decides which existing records the reconciler is allowed to update or delete. Records it
+/// does not own are left alone, and desired records are stamped with the ownership marker
+/// before they are created or updated.
+#[derive(Debug, Clone, Default)]
+pub enum Ownership {
+    /// Every record in the zone is managed.
+    #[default]
+    All,
+    /// Records carrying this tag, e.g. `owner:team-x`.
+    Tag(String),
+    /// Records whose comment starts with this prefix.
+    CommentPrefix(String),
+}
+
+impl Ownership {
+    pub fn owns(&self, record: &RecordMessage) -> bool {
+        match self {
+            Self::All => true,
+            Self::Tag(tag) => record.tags().is_some_and(|tags| tags.contains(tag)),
+            Self::CommentPrefix(prefix) => record.comment().is_some_and(|c| c.starts_with(prefix)),
+        }
+    }
+
+    fn mark(&self, record: &mut RecordRequest) {
+        match self {
+            Self::All => {}
+            Self::Tag(tag) => {
+                let mut tags = record.tags().cloned().unwrap_or_default();
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+                record.set_tags(Some(tags));
+            }
+            Self::CommentPrefix(prefix) => {
+                let comment = match record.comment() {
+                    Some(c) if c.starts_with(prefix) => c.to_string(),
+                    Some(c) if !c.is_empty() => format!("{} {}", prefix, c),
+                    _ => prefix.clone(),
+                };
+                record.set_comment(Some(comment));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Change {
+    Create(RecordRequest),
+    Update {
+        id: RecordId,
+        current: RecordRequest,
+        desired: RecordRequest,
+    },
+    Delete {
+        id: RecordId,
+        current: RecordRequest,
+    },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Create(r) => write!(f, "+ {}", describe(r)),
+            Self::Update {
+                current, desired, ..
+            } => {
+                let diffs: Vec<String> = changed_fields(current, desired)
+                    .into_iter()
+                    .map(|(k, old, new)| format!("{} {} -> {}", k, show(&old), show(&new)))
+                    .collect();
+                write!(f, "~ {}: {}", describe(current), diffs.join(", "))
+            }
+            Self::Delete { current, .. } => write!(f, "- {}", describe(current)),
+        }
+    }
+}
+
+/// The creates, updates and deletes needed to bring a zone in line with a desired record set.
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn count(&self, f: fn(&Change) -> bool) -> usize {
+        self.changes.iter().filter(|c| f(c)).count()
+    }
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        write!(
+            f,
+            "Plan: {} to create, {} to update, {} to delete.",
+            self.count(|c| matches!(c, Change::Create(_))),
+            self.count(|c| matches!(c, Change::Update { .. })),
+            self.count(|c| matches!(c, Change::Delete { .. })),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct ApplyReport {
+    pub dry_run: bool,
+    pub results: Vec<(Change, ApiResult<()>)>,
+}
+
+impl ApplyReport {
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|(_, r)| r.is_ok())
+    }
+
+    pub fn into_result(self) -> ApiResult<()> {
+        let failed: Vec<String> = self
+            .results
+            .iter()
+            .filter_map(|(c, r)| r.as_ref().err().map(|e| format!("{} ({})", c, e)))
+            .collect();
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::new(failed.join("; ")))
+        }
+    }
+}
+
+/// Keeps a zone's records in line with a desired set.
+///
+/// Records are matched by type, name and content. Desired records without an exact match are
+/// paired with leftover owned records of the same type and name as updates, so changing an A
+/// record's address is planned as one update rather than a delete and a create.
+pub struct Reconciler {
+    c: Cloudflare,
+    zone_id: ZoneId,
+    ownership: Ownership,
+    dry_run: bool,
+}
+
+impl Reconciler {
+    pub fn new(c: &Cloudflare, zone_id: ZoneId) -> Self {
+        Self {
+            c: c.clone(),
+            zone_id,
+            ownership: Ownership::default(),
+            dry_run: false,
+        }
+    }
+
+    pub fn with_ownership(mut self, ownership: Ownership) -> Self {
+        self.ownership = ownership;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub async fn plan<I>(&self, desired: I) -> ApiResult<Plan>
+    where
+        I: IntoIterator,
+        I::Item: Into<RecordRequest>,
+    {
+        let current = self.c.dns().list_all_records(&self.zone_id).await?;
+        let desired = desired.into_iter().map(Into::into).collect();
+        Ok(compute_plan(&current, desired, &self.ownership))
+    }
+
+    /// Applies deletes first, then updates, then creates, so that a record which changes type
+    /// (a CNAME replaced by an A record, say) never collides with its predecessor. Updates are
+    /// sent as PATCH, so fields the desired record leaves unset keep their current values.
+    /// Failures are collected rather than aborting the run.
+    pub async fn apply(&self, plan: &Plan) -> ApplyReport {
+        let mut ordered: Vec<&Change> = plan.changes.iter().collect();
+        ordered.sort_by_key(|c| match c {
+            Change::Delete { .. } => 0,
+            Change::Update { .. } => 1,
+            Change::Create(_) => 2,
+        });
+
+        let dns = self.c.dns();
+        let mut results = vec![];
+        for change in ordered {
+            if self.dry_run {
+                info!("dry run: {}", change);
+                results.push((change.clone(), Ok(())));
+                continue;
+            }
+            info!("applying: {}", change);
+            let res = match change {
+                Change::Create(r) => dns
+                    .create_record(&self.zone_id, r.clone())
+                    .await
+                    .and_then(|r| r.into_result().map(|_| ())),
+                Change::Update { id, desired, .. } => dns
+                    .update_record(&self.zone_id, id, desired.clone())
+                    .await
+                    .and_then(|r| r.into_result().map(|_| ())),
+                Change::Delete { id, .. } => dns
+                    .delete_record(&self.zone_id, id)
+                    .await
+                    .and_then(|r| r.into_result().map(|_| ())),
+            };
+            results.push((change.clone(), res));
+        }
+
+        ApplyReport {
+            dry_run: self.dry_run,
+            results,
+        }
+    }
+}
+
+/// Computes the plan without talking to the API.
+pub fn compute_plan(
+    current: &[RecordMessage],
+    desired: Vec<RecordRequest>,
+    ownership: &Ownership,
+) -> Plan {
+    let mut owned: Vec<Option<(RecordId, RecordRequest)>> = current
+        .iter()
+        .filter(|r| ownership.owns(r))
+        .filter_map(|r| Some((r.id()?.clone(), RecordRequest::from(r.clone()))))
+        .map(Some)
+        .collect();
+
+    let mut changes = vec![];
+    let mut unmatched = vec![];
+    for mut want in desired {
+        ownership.mark(&mut want);
+        let k = key(&want);
+        let found = owned
+            .iter_mut()
+            .find(|o| o.as_ref().is_some_and(|(_, have)| key(have) == k))
+            .and_then(|slot| slot.take());
+        match found {
+            Some((id, have)) => {
+                if !changed_fields(&have, &want).is_empty() {
+                    changes.push(Change::Update {
+                        id,
+                        current: have,
+                        desired: want,
+                    });
+                }
+            }
+            None => unmatched.push(want),
+        }
+    }
+
+    for want in unmatched {
+        let found = owned.iter_mut().find(|o| {
+            o.as_ref().is_some_and(|(_, have)| {
+                have.record_type() == want.record_type() && name(have) == name(&want)
+            })
+        });
+        match found.and_then(|slot| slot.take()) {
+            Some((id, have)) => changes.push(Change::Update {
+                id,
+                current: have,
+                desired: want,
+            }),
+            None => changes.push(Change::Create(want)),
+        }
+    }
+
+    for (id, have) in owned.into_iter().flatten() {
+        changes.push(Change::Delete { id, current: have });
+    }
+
+    Plan { changes }
+}
+
+fn name(r: &RecordRequest) -> String {
+    r.name()
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+fn content(r: &RecordRequest) -> String {
+    let c = r.content().unwrap_or_default();
+    match r {
+        RecordRequest::CNAME(_) | RecordRequest::MX(_) => {
+            c.trim_end_matches('.').to_ascii_lowercase()
+        }
+        _ => c,
+    }
+}
+
+fn key(r: &RecordRequest) -> (&str, String, String) {
+    (r.record_type(), name(r), content(r))
+}
+
+/// Fields set on `desired` that differ from `current`. Fields the desired record leaves unset
+/// are not compared, so omitting e.g. `ttl` keeps whatever the zone has.
+fn changed_fields(current: &RecordRequest, desired: &RecordRequest) -> Vec<(String, Value, Value)> {
+    let (Value::Object(have), Value::Object(want)) = (to_value(current), to_value(desired)) else {
+        return vec![];
+    };
+    let mut diffs = vec![];
+    for (k, new) in want {
+        let old = have.get(&k).cloned().unwrap_or(Value::Null);
+        let same = match k.as_str() {
+            "type" => true,
+            "name" => name(current) == name(desired),
+            "content" => content(current) == content(desired),
+            _ => old == new,
+        };
+        if !same {
+            diffs.push((k, old, new));
+        }
+    }
+    diffs
+}
+
+fn to_value(r: &RecordRequest) -> Value {
+    let mut v = serde_json::to_value(r).unwrap_or(Value::Null);
+    if let Some(Value::Array(tags)) = v.get_mut("tags") {
+        tags.sort_by_key(|t| t.to_string());
+    }
+    v
+}
+
+fn describe(r: &RecordRequest) -> String {
+    format!(
+        "{} {} {}",
+        r.record_type(),
+        r.name().unwrap_or_default(),
+        r.content().unwrap_or_default()
+    )
+}
+
+fn show(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        Value::Null => "(unset)".to_string(),
+        v => v.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::{ARecord, ARecordRequest, TXTRecord, TXTRecordRequest};
+
+    fn id(n: u8) -> RecordId {
+        format!("{:032x}", n).parse().unwrap()
+    }
+
+    fn a(n: u8, name: &str, ip: [u8; 4], tags: &[&str]) -> RecordMessage {
+        RecordMessage::A(ARecord {
+            id: Some(id(n)),
+            name: Some(name.to_string()),
+            content: Some(Ipv4Addr::from(ip)),
+            ttl: Some(300),
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn plans_creates_updates_and_deletes() {
+        let current = vec![
+            a(1, "keep.example.com", [192, 0, 2, 1], &[]),
+            a(2, "move.example.com", [192, 0, 2, 2], &[]),
+            a(3, "gone.example.com", [192, 0, 2, 3], &[]),
+        ];
+        let desired = vec![
+            RecordRequest::A(ARecordRequest {
+                name: Some("keep.example.com".to_string()),
+                content: Some(Ipv4Addr::new(192, 0, 2, 1)),
+                ttl: Some(300),
+                ..Default::default()
+            }),
+            RecordRequest::A(ARecordRequest {
+                name: Some("move.example.com".to_string()),
+                content: Some(Ipv4Addr::new(192, 0, 2, 20)),
+                ..Default::default()
+            }),
+            RecordRequest::TXT(TXTRecordRequest {
+                name: Some("new.example.com".to_string()),
+                content: Some("hello".to_string()),
+                ..Default::default()
+            }),
+        ];
+
+        let plan = compute_plan(&current, desired, &Ownership::All);
+        assert_eq!(plan.changes.len(), 3);
+        assert!(matches!(&plan.changes[0], Change::Update { id: i, .. } if *i == id(2)));
+        assert!(matches!(
+            &plan.changes[1],
+            Change::Create(RecordRequest::TXT(_))
+        ));
+        assert!(matches!(&plan.changes[2], Change::Delete { id: i, .. } if *i == id(3)));
+
+        let rendered = plan.to_string();
+        assert!(
+            rendered.contains("~ A move.example.com 192.0.2.2: content 192.0.2.2 -> 192.0.2.20")
+        );
+        assert!(rendered.ends_with("Plan: 1 to create, 1 to update, 1 to delete."));
+    }
+
+    #[test]
+    fn only_touches_owned_records() {
+        let current = vec![
+            a(1, "mine.example.com", [192, 0, 2, 1], &["owner:team-x"]),
+            a(2, "theirs.example.com", [192, 0, 2, 2], &["owner:team-y"]),
+            RecordMessage::TXT(TXTRecord {
+                id: Some(id(3)),
+                name: Some("untagged.example.com".to_string()),
+                content: Some("x".to_string()),
+                ..Default::default()
+            }),
+        ];
+        let desired = vec![RecordRequest::A(ARecordRequest {
+            name: Some("mine.example.com".to_string()),
+            content: Some(Ipv4Addr::new(192, 0, 2, 1)),
+            ..Default::default()
+        })];
+
+        let plan = compute_plan(
+            &current,
+            desired,
+            &Ownership::Tag("owner:team-x".to_string()),
+        );
+        assert!(plan.is_empty(), "{}", plan);
+
+        let plan = compute_plan(
+            &current,
+            vec![],
+            &Ownership::Tag("owner:team-x".to_string()),
+        );
+        assert_eq!(plan.changes.len(), 1);
+        assert!(matches!(&plan.changes[0], Change::Delete { id: i, .. } if *i == id(1)));
+    }
+}
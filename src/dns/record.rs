@@ -1,16 +1,18 @@
 use std::net::{Ipv4Addr, Ipv6Addr};
 
-use super::{dns_settings::DnsSettings, DnsCommon, RecordMessage, ToRecordMessage};
+use crate::{RecordId, Timestamp, ZoneId};
+
+use super::{dns_settings::DnsSettings, DnsCommon, RecordMessage, RecordRequest, ToRecordMessage};
 use cloudflare_derive::DnsCommon;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default, DnsCommon)]
 pub struct ARecord {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
+    pub id: Option<RecordId>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub zone_id: Option<String>,
+    pub zone_id: Option<ZoneId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub zone_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -22,6 +24,8 @@ pub struct ARecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxiable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub proxied: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub settings: Option<DnsSettings>,
@@ -30,11 +34,13 @@ pub struct ARecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ttl: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub modified_on: Option<String>,
+    pub created_on: Option<Timestamp>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub comment_modified_on: Option<String>,
+    pub modified_on: Option<Timestamp>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tags_modified_on: Option<String>,
+    pub comment_modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags_modified_on: Option<Timestamp>,
 }
 
 impl ToRecordMessage for ARecord {
@@ -46,9 +52,9 @@ impl ToRecordMessage for ARecord {
 #[derive(Serialize, Deserialize, Debug, Clone, Default, DnsCommon)]
 pub struct AAAARecord {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
+    pub id: Option<RecordId>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub zone_id: Option<String>,
+    pub zone_id: Option<ZoneId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub zone_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -60,6 +66,8 @@ pub struct AAAARecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxiable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub proxied: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub settings: Option<DnsSettings>,
@@ -68,11 +76,13 @@ pub struct AAAARecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ttl: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub modified_on: Option<String>,
+    pub created_on: Option<Timestamp>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub comment_modified_on: Option<String>,
+    pub modified_on: Option<Timestamp>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tags_modified_on: Option<String>,
+    pub comment_modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags_modified_on: Option<Timestamp>,
 }
 
 impl ToRecordMessage for AAAARecord {
@@ -84,9 +94,9 @@ impl ToRecordMessage for AAAARecord {
 #[derive(Serialize, Deserialize, Debug, Clone, Default, DnsCommon)]
 pub struct CNAMERecord {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
+    pub id: Option<RecordId>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub zone_id: Option<String>,
+    pub zone_id: Option<ZoneId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub zone_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -97,6 +107,24 @@ pub struct CNAMERecord {
     pub content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxiable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<DnsSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags_modified_on: Option<Timestamp>,
 }
 
 impl ToRecordMessage for CNAMERecord {
@@ -111,9 +139,9 @@ pub struct Meta(Value);
 #[derive(Serialize, Deserialize, Debug, Clone, Default, DnsCommon)]
 pub struct MXRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
+    pub id: Option<RecordId>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub zone_id: Option<String>,
+    pub zone_id: Option<ZoneId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub zone_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -137,9 +165,13 @@ pub struct MXRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub created_on: Option<String>,
+    pub created_on: Option<Timestamp>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub modified_on: Option<String>,
+    pub modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags_modified_on: Option<Timestamp>,
 }
 
 impl ToRecordMessage for MXRecord {
@@ -151,9 +183,9 @@ impl ToRecordMessage for MXRecord {
 #[derive(Serialize, Deserialize, Debug, Clone, Default, DnsCommon)]
 pub struct TXTRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
+    pub id: Option<RecordId>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub zone_id: Option<String>,
+    pub zone_id: Option<ZoneId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub zone_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -175,9 +207,13 @@ pub struct TXTRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub created_on: Option<String>,
+    pub created_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub modified_on: Option<String>,
+    pub comment_modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags_modified_on: Option<Timestamp>,
 }
 
 impl ToRecordMessage for TXTRecord {
@@ -185,3 +221,299 @@ impl ToRecordMessage for TXTRecord {
         RecordMessage::TXT(self)
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ARecordRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Ipv4Addr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<DnsSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl From<ARecord> for ARecordRequest {
+    fn from(r: ARecord) -> Self {
+        Self {
+            name: r.name,
+            content: r.content,
+            proxied: r.proxied,
+            ttl: r.ttl,
+            settings: r.settings,
+            comment: r.comment,
+            tags: r.tags,
+        }
+    }
+}
+
+impl From<ARecordRequest> for RecordRequest {
+    fn from(r: ARecordRequest) -> Self {
+        RecordRequest::A(r)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AAAARecordRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Ipv6Addr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<DnsSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl From<AAAARecord> for AAAARecordRequest {
+    fn from(r: AAAARecord) -> Self {
+        Self {
+            name: r.name,
+            content: r.content,
+            proxied: r.proxied,
+            ttl: r.ttl,
+            settings: r.settings,
+            comment: r.comment,
+            tags: r.tags,
+        }
+    }
+}
+
+impl From<AAAARecordRequest> for RecordRequest {
+    fn from(r: AAAARecordRequest) -> Self {
+        RecordRequest::AAAA(r)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CNAMERecordRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<DnsSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl From<CNAMERecord> for CNAMERecordRequest {
+    fn from(r: CNAMERecord) -> Self {
+        Self {
+            name: r.name,
+            content: r.content,
+            proxied: r.proxied,
+            ttl: r.ttl,
+            settings: r.settings,
+            comment: r.comment,
+            tags: r.tags,
+        }
+    }
+}
+
+impl From<CNAMERecordRequest> for RecordRequest {
+    fn from(r: CNAMERecordRequest) -> Self {
+        RecordRequest::CNAME(r)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MXRecordRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<DnsSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl From<MXRecord> for MXRecordRequest {
+    fn from(r: MXRecord) -> Self {
+        Self {
+            name: r.name,
+            content: r.content,
+            priority: r.priority,
+            proxied: r.proxied,
+            ttl: r.ttl,
+            settings: r.settings,
+            comment: r.comment,
+            tags: r.tags,
+        }
+    }
+}
+
+impl From<MXRecordRequest> for RecordRequest {
+    fn from(r: MXRecordRequest) -> Self {
+        RecordRequest::MX(r)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TXTRecordRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<DnsSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl From<TXTRecord> for TXTRecordRequest {
+    fn from(r: TXTRecord) -> Self {
+        Self {
+            name: r.name,
+            content: r.content,
+            proxied: r.proxied,
+            ttl: r.ttl,
+            settings: r.settings,
+            comment: r.comment,
+            tags: r.tags,
+        }
+    }
+}
+
+impl From<TXTRecordRequest> for RecordRequest {
+    fn from(r: TXTRecordRequest) -> Self {
+        RecordRequest::TXT(r)
+    }
+}
+
+/// A record of a type without its own variant, such as NS, SRV or CAA. Fields not common to
+/// every type, like SRV's `data` or the `priority`, are kept in `extra` as the API sent them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, DnsCommon)]
+pub struct OtherRecord {
+    #[serde(rename = "type")]
+    pub record_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<RecordId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zone_id: Option<ZoneId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zone_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxiable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags_modified_on: Option<Timestamp>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ToRecordMessage for OtherRecord {
+    fn to_record_message(self) -> RecordMessage {
+        RecordMessage::Other(self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct OtherRecordRequest {
+    #[serde(rename = "type")]
+    pub record_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Fields the API sets on a record and rejects or ignores in a request body.
+const READ_ONLY_FIELDS: &[&str] = &[
+    "id",
+    "zone_id",
+    "zone_name",
+    "proxiable",
+    "locked",
+    "meta",
+    "created_on",
+    "modified_on",
+    "comment_modified_on",
+    "tags_modified_on",
+];
+
+impl From<OtherRecord> for OtherRecordRequest {
+    fn from(mut r: OtherRecord) -> Self {
+        r.extra
+            .retain(|k, _| !READ_ONLY_FIELDS.contains(&k.as_str()));
+        Self {
+            record_type: r.record_type,
+            name: r.name,
+            content: r.content,
+            proxied: r.proxied,
+            ttl: r.ttl,
+            comment: r.comment,
+            tags: r.tags,
+            extra: r.extra,
+        }
+    }
+}
+
+impl From<OtherRecordRequest> for RecordRequest {
+    fn from(r: OtherRecordRequest) -> Self {
+        RecordRequest::Other(r)
+    }
+}
@@ -0,0 +1,193 @@
+use hyper::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{BatchDelete, BatchUpdate, RecordMessage, RecordRequest};
+use crate::{
+    ApiError, ApiErrorKind, ApiResponse, ApiResult, Cloudflare, PerformOptions, RecordId, ZoneId,
+};
+
+/// Scanned records to add to the zone (`accepts`) or discard (`rejects`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ScanReviewRequest {
+    #[serde(default)]
+    pub accepts: Vec<BatchUpdate>,
+    #[serde(default)]
+    pub rejects: Vec<BatchDelete>,
+}
+
+impl ScanReviewRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts a record as returned by [`CloudflareDnsScan::list`], optionally after editing
+    /// it. The scan's id says which record is accepted, so a record without one is an error.
+    pub fn accept(mut self, record: RecordMessage) -> ApiResult<Self> {
+        let id = record.id().cloned();
+        let record = RecordRequest::from(record);
+        let id = id.ok_or_else(|| {
+            ApiError::with_kind(
+                ApiErrorKind::Invalid,
+                format!(
+                    "scanned {} record {} has no id",
+                    record.record_type(),
+                    record.name().unwrap_or_default()
+                ),
+            )
+        })?;
+        self.accepts.push(BatchUpdate { id, record });
+        Ok(self)
+    }
+
+    pub fn reject(mut self, id: RecordId) -> Self {
+        self.rejects.push(BatchDelete { id });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accepts.is_empty() && self.rejects.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ScanReviewResult {
+    /// The records as added to the zone.
+    #[serde(default)]
+    pub accepts: Vec<RecordMessage>,
+    #[serde(default)]
+    pub rejects: Vec<RecordId>,
+}
+
+/// Cloudflare's scan of the zone's current nameservers, used when onboarding a zone. Scanned
+/// records wait for review and are only added to the zone once accepted.
+pub struct CloudflareDnsScan {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareDnsScan {
+    /// Starts a scan in the background. Results show up in [`Self::list`].
+    pub async fn trigger(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Value>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::POST,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dns_records/scan/trigger", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    /// Scanned records awaiting review.
+    pub async fn list(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Vec<RecordMessage>>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dns_records/scan/review", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn review(
+        &self,
+        zone_id: &ZoneId,
+        req: ScanReviewRequest,
+    ) -> ApiResult<ApiResponse<ScanReviewResult>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::POST,
+                PerformOptions {
+                    path: format!("zones/{}/dns_records/scan/review", zone_id),
+                    params: None,
+                    body: Some(req),
+                },
+            )
+            .await
+    }
+
+    pub async fn accept(
+        &self,
+        zone_id: &ZoneId,
+        records: Vec<RecordMessage>,
+    ) -> ApiResult<ApiResponse<ScanReviewResult>> {
+        let req = records
+            .into_iter()
+            .try_fold(ScanReviewRequest::new(), ScanReviewRequest::accept)?;
+        self.review(zone_id, req).await
+    }
+
+    pub async fn reject(
+        &self,
+        zone_id: &ZoneId,
+        ids: Vec<RecordId>,
+    ) -> ApiResult<ApiResponse<ScanReviewResult>> {
+        let req = ids
+            .into_iter()
+            .fold(ScanReviewRequest::new(), ScanReviewRequest::reject);
+        self.review(zone_id, req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_review_body() {
+        let scanned: Vec<RecordMessage> = serde_json::from_value(serde_json::json!([
+            {
+                "type": "A",
+                "id": "023e105f4ecef8ad9ca31a8372d0c353",
+                "name": "www.example.com",
+                "content": "192.0.2.1",
+                "ttl": 300
+            },
+            {
+                "type": "SRV",
+                "id": "4b1b6e4a1c3f2b8e9d0a7c6f5e4d3c2b",
+                "name": "_sip._tcp.example.com",
+                "content": "1 5060 sip.example.com",
+                "priority": 10
+            }
+        ]))
+        .unwrap();
+        assert!(matches!(scanned[1], RecordMessage::Other(_)));
+        let reject: RecordId = "372e67954025e0ba6aaa6d586b9e0b59".parse().unwrap();
+        let req = scanned
+            .into_iter()
+            .try_fold(ScanReviewRequest::new(), ScanReviewRequest::accept)
+            .unwrap()
+            .reject(reject);
+        let body = serde_json::to_value(&req).unwrap();
+        assert_eq!(body["accepts"].as_array().unwrap().len(), 2);
+        assert_eq!(body["accepts"][0]["id"], "023e105f4ecef8ad9ca31a8372d0c353");
+        assert_eq!(body["accepts"][0]["type"], "A");
+        assert_eq!(body["accepts"][0]["content"], "192.0.2.1");
+        assert_eq!(body["accepts"][1]["type"], "SRV");
+        assert_eq!(body["accepts"][1]["priority"], 10);
+        assert_eq!(
+            body["rejects"],
+            serde_json::json!([{"id": "372e67954025e0ba6aaa6d586b9e0b59"}])
+        );
+    }
+
+    #[test]
+    fn accepting_a_record_without_an_id_fails() {
+        let record: RecordMessage = serde_json::from_value(
+            serde_json::json!({"type": "TXT", "name": "example.com", "content": "v=spf1 -all"}),
+        )
+        .unwrap();
+        let err = ScanReviewRequest::new().accept(record).unwrap_err();
+        assert_eq!(err.kind(), ApiErrorKind::Invalid);
+        assert!(err.to_string().contains("example.com"), "{}", err);
+    }
+}
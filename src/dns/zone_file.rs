@@ -0,0 +1,703 @@
+use std::{
+    fmt::Display,
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    AAAARecord, ARecord, CNAMERecord, DnsCommon, MXRecord, OtherRecord, RecordMessage, TXTRecord,
+};
+
+const CF_TAGS: &str = "cf_tags=";
+const CF_PROXIED: &str = "cf-proxied:";
+const TXT_CHUNK: usize = 255;
+
+#[derive(Debug, Clone)]
+pub struct ZoneFileError(String);
+
+impl Display for ZoneFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ZoneFileError: {}", self.0)
+    }
+}
+
+impl std::error::Error for ZoneFileError {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ImportRecordsResult {
+    pub recs_added: usize,
+    pub total_records_parsed: usize,
+}
+
+/// Result of parsing a zone file. Entries whose type has no [`RecordMessage`] variant (SOA and
+/// NS records, for instance) are not dropped silently but returned verbatim in `unsupported`.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedZoneFile {
+    pub records: Vec<RecordMessage>,
+    pub unsupported: Vec<String>,
+}
+
+/// Serializes records into an RFC 1035 zone file in the same layout Cloudflare's export endpoint
+/// uses, including the `cf_tags=cf-proxied:<bool>` comment that carries the proxied flag.
+pub fn to_zone_file(records: &[RecordMessage], origin: Option<&str>) -> String {
+    let mut out = String::new();
+    if let Some(origin) = origin {
+        out.push_str(&format!("$ORIGIN {}.\n", origin.trim_end_matches('.')));
+    }
+    for record in records {
+        if let Some(line) = record_line(record) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn record_line(record: &RecordMessage) -> Option<String> {
+    let name = record.clone().get_name()?;
+    let (rtype, rdata, ttl, proxied, comment) = match record {
+        RecordMessage::A(r) => (
+            "A",
+            r.content?.to_string(),
+            r.ttl,
+            r.proxied,
+            r.comment.clone(),
+        ),
+        RecordMessage::AAAA(r) => (
+            "AAAA",
+            r.content?.to_string(),
+            r.ttl,
+            r.proxied,
+            r.comment.clone(),
+        ),
+        RecordMessage::CNAME(r) => (
+            "CNAME",
+            fqdn(r.content.as_ref()?),
+            r.ttl,
+            r.proxied,
+            r.comment.clone(),
+        ),
+        RecordMessage::MX(r) => (
+            "MX",
+            format!("{} {}", r.priority.unwrap_or(0), fqdn(r.content.as_ref()?)),
+            r.ttl,
+            r.proxied,
+            r.comment.clone(),
+        ),
+        RecordMessage::TXT(r) => (
+            "TXT",
+            quote_txt(r.content.as_ref()?),
+            r.ttl,
+            r.proxied,
+            r.comment.clone(),
+        ),
+        RecordMessage::Other(r) => (
+            r.record_type.as_str(),
+            other_rdata(r)?,
+            r.ttl,
+            r.proxied,
+            r.comment.clone(),
+        ),
+    };
+
+    let mut line = format!(
+        "{}\t{}\tIN\t{}\t{}",
+        fqdn(&name),
+        ttl.unwrap_or(1),
+        rtype,
+        rdata
+    );
+    let mut trailer = vec![];
+    if let Some(comment) = comment.filter(|c| !c.is_empty()) {
+        trailer.push(comment.replace('\n', " "));
+    }
+    if let Some(proxied) = proxied {
+        trailer.push(format!("{}{}{}", CF_TAGS, CF_PROXIED, proxied));
+    }
+    if !trailer.is_empty() {
+        line.push_str(" ; ");
+        line.push_str(&trailer.join(" "));
+    }
+    Some(line)
+}
+
+/// The API reports target names without the trailing dot, which a zone file would read as
+/// relative to `$ORIGIN`.
+fn other_rdata(r: &OtherRecord) -> Option<String> {
+    let content = r.content.as_deref()?;
+    let priority = r.extra.get("priority").and_then(|p| p.as_u64());
+    Some(match r.record_type.as_str() {
+        "NS" | "PTR" | "DNAME" => fqdn(content),
+        "SRV" => {
+            let mut parts: Vec<&str> = content.split_whitespace().collect();
+            let target = parts.pop().map(fqdn).unwrap_or_default();
+            format!("{} {} {}", priority.unwrap_or(0), parts.join(" "), target)
+        }
+        _ => content.to_string(),
+    })
+}
+
+fn fqdn(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+fn quote_txt(content: &str) -> String {
+    if content.starts_with('"') {
+        return content.to_string();
+    }
+    let mut chunks = vec![];
+    let mut current = String::new();
+    for c in content.chars() {
+        if current.len() + c.len_utf8() > TXT_CHUNK {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+        .iter()
+        .map(|c| format!("\"{}\"", c.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses an RFC 1035 zone file into records. Relative owner names and targets are qualified
+/// with `$ORIGIN` (or `origin` until the file sets one), and names are returned without the
+/// trailing dot, the way the API reports them.
+pub fn parse_zone_file(input: &str, origin: Option<&str>) -> Result<ParsedZoneFile, ZoneFileError> {
+    let mut parsed = ParsedZoneFile::default();
+    let mut origin = origin.map(|o| o.trim_end_matches('.').to_string());
+    let mut default_ttl: Option<usize> = None;
+    let mut last_owner: Option<String> = None;
+
+    for entry in entries(input)? {
+        let tokens = &entry.tokens;
+        if tokens.is_empty() {
+            continue;
+        }
+        let lineno = entry.line;
+
+        match tokens[0].text.to_ascii_uppercase().as_str() {
+            "$ORIGIN" => {
+                let o = tokens
+                    .get(1)
+                    .ok_or_else(|| err(lineno, "$ORIGIN requires a name"))?;
+                origin = Some(absolute(&o.text, origin.as_deref()));
+                continue;
+            }
+            "$TTL" => {
+                let t = tokens
+                    .get(1)
+                    .ok_or_else(|| err(lineno, "$TTL requires a value"))?;
+                default_ttl =
+                    Some(parse_ttl(&t.text).ok_or_else(|| err(lineno, "invalid $TTL value"))?);
+                continue;
+            }
+            d if d.starts_with('$') => {
+                return Err(err(lineno, &format!("unsupported directive {}", d)));
+            }
+            _ => {}
+        }
+
+        let mut rest = &tokens[..];
+        let owner = if entry.indented {
+            last_owner
+                .clone()
+                .ok_or_else(|| err(lineno, "record has no owner name"))?
+        } else {
+            let owner = absolute(&rest[0].text, origin.as_deref());
+            rest = &rest[1..];
+            owner
+        };
+        last_owner = Some(owner.clone());
+
+        let mut ttl = default_ttl;
+        let mut rtype = None;
+        while let Some(tok) = rest.first() {
+            rest = &rest[1..];
+            if tok.quoted {
+                return Err(err(lineno, "expected record type"));
+            }
+            if let Some(t) = parse_ttl(&tok.text) {
+                ttl = Some(t);
+                continue;
+            }
+            match tok.text.to_ascii_uppercase().as_str() {
+                "IN" | "CH" | "HS" | "CS" => continue,
+                t => {
+                    rtype = Some(t.to_string());
+                    break;
+                }
+            }
+        }
+        let rtype = rtype.ok_or_else(|| err(lineno, "missing record type"))?;
+
+        let (comment, proxied) = split_comment(entry.comment.as_deref());
+        let rdata: Vec<&Token> = rest.iter().collect();
+        let record = match rtype.as_str() {
+            "A" => RecordMessage::A(ARecord {
+                name: Some(owner),
+                content: Some(
+                    Ipv4Addr::from_str(&single(&rdata, lineno)?)
+                        .map_err(|e| err(lineno, &e.to_string()))?,
+                ),
+                ttl,
+                proxied,
+                comment,
+                ..Default::default()
+            }),
+            "AAAA" => RecordMessage::AAAA(AAAARecord {
+                name: Some(owner),
+                content: Some(
+                    Ipv6Addr::from_str(&single(&rdata, lineno)?)
+                        .map_err(|e| err(lineno, &e.to_string()))?,
+                ),
+                ttl,
+                proxied,
+                comment,
+                ..Default::default()
+            }),
+            "CNAME" => RecordMessage::CNAME(CNAMERecord {
+                name: Some(owner),
+                content: Some(absolute(&single(&rdata, lineno)?, origin.as_deref())),
+                ttl,
+                proxied,
+                comment,
+                ..Default::default()
+            }),
+            "MX" => {
+                if rdata.len() != 2 {
+                    return Err(err(lineno, "MX record requires a priority and a host"));
+                }
+                let priority = rdata[0]
+                    .text
+                    .parse::<usize>()
+                    .map_err(|_| err(lineno, "invalid MX priority"))?;
+                RecordMessage::MX(MXRecord {
+                    name: Some(owner),
+                    content: Some(absolute(&rdata[1].text, origin.as_deref())),
+                    priority: Some(priority),
+                    ttl,
+                    proxied,
+                    comment,
+                    ..Default::default()
+                })
+            }
+            "TXT" => {
+                if rdata.is_empty() {
+                    return Err(err(lineno, "TXT record requires content"));
+                }
+                RecordMessage::TXT(TXTRecord {
+                    name: Some(owner),
+                    content: Some(rdata.iter().map(|t| t.text.as_str()).collect()),
+                    ttl,
+                    proxied,
+                    comment,
+                    ..Default::default()
+                })
+            }
+            _ => {
+                parsed.unsupported.push(entry.raw.clone());
+                continue;
+            }
+        };
+        parsed.records.push(record);
+    }
+
+    Ok(parsed)
+}
+
+fn err(line: usize, msg: &str) -> ZoneFileError {
+    ZoneFileError(format!("line {}: {}", line, msg))
+}
+
+fn single(rdata: &[&Token], line: usize) -> Result<String, ZoneFileError> {
+    match rdata {
+        [tok] => Ok(tok.text.clone()),
+        _ => Err(err(line, "expected exactly one value")),
+    }
+}
+
+fn absolute(name: &str, origin: Option<&str>) -> String {
+    if name == "@" {
+        return origin.unwrap_or_default().to_string();
+    }
+    if let Some(name) = name.strip_suffix('.') {
+        return name.to_string();
+    }
+    match origin {
+        Some(origin) if !origin.is_empty() => format!("{}.{}", name, origin),
+        _ => name.to_string(),
+    }
+}
+
+fn parse_ttl(s: &str) -> Option<usize> {
+    if let Ok(v) = s.parse::<usize>() {
+        return Some(v);
+    }
+    let mut total = 0;
+    let mut num = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            num.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 604800,
+            _ => return None,
+        };
+        let n = num.parse::<usize>().ok()?.checked_mul(unit)?;
+        total = n.checked_add(total)?;
+        num.clear();
+    }
+    if !num.is_empty() {
+        return None;
+    }
+    Some(total)
+}
+
+/// Separates Cloudflare's `cf_tags=...` annotation from the free-form part of a comment.
+fn split_comment(comment: Option<&str>) -> (Option<String>, Option<bool>) {
+    let Some(comment) = comment else {
+        return (None, None);
+    };
+    let mut proxied = None;
+    let mut words = vec![];
+    for word in comment.split_whitespace() {
+        match word.strip_prefix(CF_TAGS) {
+            Some(tags) => {
+                for tag in tags.split(',') {
+                    if let Some(v) = tag.strip_prefix(CF_PROXIED) {
+                        proxied = v.parse::<bool>().ok();
+                    }
+                }
+            }
+            None => words.push(word),
+        }
+    }
+    let comment = words.join(" ");
+    let comment = if comment.is_empty() {
+        None
+    } else {
+        Some(comment)
+    };
+    (comment, proxied)
+}
+
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+struct Entry {
+    line: usize,
+    raw: String,
+    indented: bool,
+    tokens: Vec<Token>,
+    comment: Option<String>,
+}
+
+/// Splits the input into logical entries, joining lines wrapped in parentheses and collecting
+/// comments.
+fn entries(input: &str) -> Result<Vec<Entry>, ZoneFileError> {
+    let mut out = vec![];
+    let mut current: Option<Entry> = None;
+    let mut depth = 0usize;
+
+    for (i, line) in input.lines().enumerate() {
+        let lineno = i + 1;
+        let entry = current.get_or_insert_with(|| Entry {
+            line: lineno,
+            raw: String::new(),
+            indented: line.starts_with([' ', '\t']),
+            tokens: vec![],
+            comment: None,
+        });
+        if !entry.raw.is_empty() {
+            entry.raw.push('\n');
+        }
+        entry.raw.push_str(line);
+
+        let mut chars = line.chars().peekable();
+        // Bytes rather than chars, so `\DDD` escapes can spell out multi-byte UTF-8.
+        let mut word: Vec<u8> = vec![];
+        let mut in_word = false;
+        let mut quoted = false;
+        while let Some(c) = chars.next() {
+            if quoted {
+                match c {
+                    '\\' => unescape(&mut chars, &mut word).map_err(|m| err(lineno, m))?,
+                    '"' => {
+                        entry.tokens.push(Token {
+                            text: take_word(&mut word, lineno)?,
+                            quoted: true,
+                        });
+                        quoted = false;
+                    }
+                    _ => push_char(&mut word, c),
+                }
+                continue;
+            }
+            match c {
+                '"' => {
+                    if in_word {
+                        entry.tokens.push(Token {
+                            text: take_word(&mut word, lineno)?,
+                            quoted: false,
+                        });
+                        in_word = false;
+                    }
+                    quoted = true;
+                }
+                ';' => {
+                    let text: String = chars.by_ref().collect();
+                    let text = text.trim();
+                    if !text.is_empty() {
+                        entry.comment = Some(match entry.comment.take() {
+                            Some(c) => format!("{} {}", c, text),
+                            None => text.to_string(),
+                        });
+                    }
+                }
+                '(' | ')' | ' ' | '\t' => {
+                    if in_word {
+                        entry.tokens.push(Token {
+                            text: take_word(&mut word, lineno)?,
+                            quoted: false,
+                        });
+                        in_word = false;
+                    }
+                    if c == '(' {
+                        depth += 1;
+                    } else if c == ')' {
+                        depth = depth
+                            .checked_sub(1)
+                            .ok_or_else(|| err(lineno, "unbalanced parentheses"))?;
+                    }
+                }
+                '\\' => {
+                    in_word = true;
+                    unescape(&mut chars, &mut word).map_err(|m| err(lineno, m))?;
+                }
+                _ => {
+                    in_word = true;
+                    push_char(&mut word, c);
+                }
+            }
+        }
+        if quoted {
+            return Err(err(lineno, "unterminated quoted string"));
+        }
+        if in_word {
+            entry.tokens.push(Token {
+                text: take_word(&mut word, lineno)?,
+                quoted: false,
+            });
+        }
+
+        if depth == 0 {
+            out.extend(current.take());
+        }
+    }
+
+    if depth != 0 {
+        return Err(err(input.lines().count(), "unbalanced parentheses"));
+    }
+    out.extend(current.take());
+    Ok(out)
+}
+
+fn push_char(word: &mut Vec<u8>, c: char) {
+    word.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+fn take_word(word: &mut Vec<u8>, line: usize) -> Result<String, ZoneFileError> {
+    String::from_utf8(std::mem::take(word))
+        .map_err(|_| err(line, "escaped bytes are not valid UTF-8"))
+}
+
+/// Decodes the escape after a backslash into `out`: `\DDD` is a single byte in decimal, and
+/// any other character stands for itself.
+fn unescape(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    out: &mut Vec<u8>,
+) -> Result<(), &'static str> {
+    let mut digits = String::new();
+    while digits.len() < 3 {
+        match chars.peek() {
+            Some(d) if d.is_ascii_digit() => {
+                digits.push(*d);
+                chars.next();
+            }
+            _ => break,
+        }
+    }
+    match digits.len() {
+        3 => out.push(
+            digits
+                .parse::<u8>()
+                .map_err(|_| "escaped byte is above 255")?,
+        ),
+        0 => push_char(out, chars.next().unwrap_or('\\')),
+        _ => out.extend_from_slice(digits.as_bytes()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZONE: &str = r#"$ORIGIN example.com.
+$TTL 3600
+@       IN SOA ns1.example.com. admin.example.com. (
+            2024010101 ; serial
+            7200 3600 1209600 3600 )
+@       IN NS ns1.example.com.
+@       300 IN A 192.0.2.1 ; cf_tags=cf-proxied:true
+www     IN CNAME @
+api     1h IN AAAA 2001:db8::1 ; backend cf_tags=cf-proxied:false
+        IN A 192.0.2.2
+@       IN MX 10 mail
+@       IN TXT "v=spf1 include:_spf.example.com ~all"
+long    IN TXT "part one;" "part \"two\""
+"#;
+
+    #[test]
+    fn parses_zone_file() {
+        let parsed = parse_zone_file(ZONE, None).unwrap();
+        assert_eq!(parsed.unsupported.len(), 2);
+        assert_eq!(parsed.records.len(), 7);
+
+        match &parsed.records[0] {
+            RecordMessage::A(r) => {
+                assert_eq!(r.name.as_deref(), Some("example.com"));
+                assert_eq!(r.content, Some(Ipv4Addr::new(192, 0, 2, 1)));
+                assert_eq!(r.ttl, Some(300));
+                assert_eq!(r.proxied, Some(true));
+                assert_eq!(r.comment, None);
+            }
+            r => panic!("unexpected record {:?}", r),
+        }
+        match &parsed.records[1] {
+            RecordMessage::CNAME(r) => {
+                assert_eq!(r.name.as_deref(), Some("www.example.com"));
+                assert_eq!(r.content.as_deref(), Some("example.com"));
+                assert_eq!(r.ttl, Some(3600));
+            }
+            r => panic!("unexpected record {:?}", r),
+        }
+        match &parsed.records[2] {
+            RecordMessage::AAAA(r) => {
+                assert_eq!(r.ttl, Some(3600));
+                assert_eq!(r.proxied, Some(false));
+                assert_eq!(r.comment.as_deref(), Some("backend"));
+            }
+            r => panic!("unexpected record {:?}", r),
+        }
+        match &parsed.records[3] {
+            RecordMessage::A(r) => assert_eq!(r.name.as_deref(), Some("api.example.com")),
+            r => panic!("unexpected record {:?}", r),
+        }
+        match &parsed.records[4] {
+            RecordMessage::MX(r) => {
+                assert_eq!(r.priority, Some(10));
+                assert_eq!(r.content.as_deref(), Some("mail.example.com"));
+            }
+            r => panic!("unexpected record {:?}", r),
+        }
+        match &parsed.records[6] {
+            RecordMessage::TXT(r) => {
+                assert_eq!(r.content.as_deref(), Some("part one;part \"two\""))
+            }
+            r => panic!("unexpected record {:?}", r),
+        }
+    }
+
+    #[test]
+    fn round_trips_records() {
+        let parsed = parse_zone_file(ZONE, None).unwrap();
+        let exported = to_zone_file(&parsed.records, Some("example.com"));
+        let reparsed = parse_zone_file(&exported, None).unwrap();
+
+        assert!(reparsed.unsupported.is_empty());
+        assert_eq!(
+            serde_json::to_value(&parsed.records).unwrap(),
+            serde_json::to_value(&reparsed.records).unwrap()
+        );
+        assert_eq!(
+            to_zone_file(&reparsed.records, Some("example.com")),
+            exported
+        );
+    }
+
+    #[test]
+    fn splits_long_txt_content() {
+        let content = "a".repeat(300);
+        let records = vec![RecordMessage::TXT(TXTRecord {
+            name: Some("example.com".to_string()),
+            content: Some(content.clone()),
+            ..Default::default()
+        })];
+        let exported = to_zone_file(&records, None);
+        assert!(exported.contains(&format!("\"{}\" \"{}\"", "a".repeat(255), "a".repeat(45))));
+
+        let reparsed = parse_zone_file(&exported, None).unwrap();
+        match &reparsed.records[0] {
+            RecordMessage::TXT(r) => assert_eq!(r.content.as_ref(), Some(&content)),
+            r => panic!("unexpected record {:?}", r),
+        }
+    }
+
+    #[test]
+    fn exports_other_record_types() {
+        let records: Vec<RecordMessage> = serde_json::from_value(serde_json::json!([
+            {"type": "NS", "name": "sub.example.com", "content": "ns1.example.net", "ttl": 300},
+            {"type": "SRV", "name": "_sip._tcp.example.com", "content": "5 5060 sip.example.com",
+             "priority": 10, "ttl": 300}
+        ]))
+        .unwrap();
+        let exported = to_zone_file(&records, None);
+        assert!(exported.contains("sub.example.com.\t300\tIN\tNS\tns1.example.net."));
+        assert!(
+            exported.contains("_sip._tcp.example.com.\t300\tIN\tSRV\t10 5 5060 sip.example.com.")
+        );
+    }
+
+    #[test]
+    fn decodes_escaped_utf8() {
+        let parsed = parse_zone_file(
+            "www 300 IN TXT \"caf\\195\\169 \\\"x\\\"\"",
+            Some("example.com"),
+        )
+        .unwrap();
+        match &parsed.records[0] {
+            RecordMessage::TXT(r) => assert_eq!(r.content.as_deref(), Some("café \"x\"")),
+            r => panic!("unexpected record {:?}", r),
+        }
+        assert!(parse_zone_file("www IN TXT \"\\256\"", Some("example.com")).is_err());
+        assert!(parse_zone_file("www IN TXT \"\\195\"", Some("example.com")).is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_ttls() {
+        assert_eq!(parse_ttl("1h30m"), Some(5400));
+        assert_eq!(parse_ttl("99999999999999w"), None);
+        assert_eq!(parse_ttl(&format!("{}s1s", usize::MAX)), None);
+    }
+
+    #[test]
+    fn rejects_malformed_records() {
+        assert!(parse_zone_file("www IN A not-an-ip", Some("example.com")).is_err());
+        assert!(parse_zone_file("www IN TXT \"unterminated", Some("example.com")).is_err());
+        assert!(parse_zone_file("www IN MX mail.example.com.", Some("example.com")).is_err());
+    }
+}
@@ -0,0 +1,595 @@
+use std::{future::Future, time::Duration};
+
+use hyper::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::IpCidr;
+use crate::{
+    AccountId, ApiError, ApiErrorKind, ApiResponse, ApiResult, Cloudflare, IdResult, ListId,
+    ListItemId, PerformOptions, QueryParams, ResponseInfo, Timestamp,
+};
+
+/// Kinds this crate does not know yet come through as `Other`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ListKind {
+    Ip,
+    Hostname,
+    Asn,
+    Redirect,
+    #[serde(untagged)]
+    Other(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct List {
+    pub id: ListId,
+    pub name: String,
+    pub kind: ListKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub num_items: usize,
+    /// Filters and rules referencing the list.
+    #[serde(default)]
+    pub num_referencing_filters: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateListRequest {
+    /// Lowercase letters, digits and underscores; referenced from expressions as `$name`.
+    pub name: String,
+    pub kind: ListKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HostnameItem {
+    /// Exact hostname, or `*.example.com` for subdomains.
+    pub url_hostname: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedirectItem {
+    pub source_url: String,
+    pub target_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_subdomains: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subpath_matching: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preserve_query_string: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preserve_path_suffix: Option<bool>,
+}
+
+/// The entry itself; which variant applies follows the list's [`ListKind`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ListItemValue {
+    Ip(IpCidr),
+    Hostname(HostnameItem),
+    Asn(u32),
+    Redirect(RedirectItem),
+}
+
+impl From<IpCidr> for ListItemValue {
+    fn from(net: IpCidr) -> Self {
+        Self::Ip(net)
+    }
+}
+
+impl From<std::net::IpAddr> for ListItemValue {
+    fn from(ip: std::net::IpAddr) -> Self {
+        Self::Ip(ip.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListItem {
+    pub id: ListItemId,
+    #[serde(flatten)]
+    pub value: ListItemValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ListItemRequest {
+    #[serde(flatten)]
+    pub value: ListItemValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl ListItemRequest {
+    pub fn new(value: impl Into<ListItemValue>) -> Self {
+        Self {
+            value: value.into(),
+            comment: None,
+        }
+    }
+
+    pub fn with_comment(mut self, comment: &str) -> Self {
+        self.comment = Some(comment.to_string());
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ListCursors {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ListCursorInfo {
+    #[serde(default)]
+    pub cursors: ListCursors,
+}
+
+/// A page of list items. Unlike most listings these pages are cursor based.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListItemsPage {
+    pub result: Option<Vec<ListItem>>,
+    pub success: bool,
+    #[serde(default)]
+    pub messages: Vec<ResponseInfo>,
+    #[serde(default)]
+    pub errors: Vec<ResponseInfo>,
+    pub result_info: Option<ListCursorInfo>,
+}
+
+impl ListItemsPage {
+    /// Cursor for the next page, if there is one.
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.result_info.as_ref()?.cursors.after.as_deref()
+    }
+
+    pub fn into_result(self) -> ApiResult<Vec<ListItem>> {
+        if !self.success {
+            return Err(crate::error_from_infos(&self.errors));
+        }
+        Ok(self.result.unwrap_or_default())
+    }
+}
+
+/// Returned by the item endpoints, which apply changes asynchronously.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListOperation {
+    pub operation_id: String,
+}
+
+/// Statuses this crate does not know yet come through as `Other` and are treated as still
+/// in progress.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkOperationStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    #[serde(untagged)]
+    Other(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkOperation {
+    pub id: String,
+    pub status: BulkOperationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<Timestamp>,
+}
+
+impl BulkOperation {
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self.status,
+            BulkOperationStatus::Completed | BulkOperationStatus::Failed
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct DeleteItemsRequest {
+    items: Vec<IdResult<ListItemId>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct UpdateListRequest {
+    description: String,
+}
+
+/// Account-level lists, referenced from rule expressions (`ip.src in $blocklist`) and, for
+/// redirect lists, from bulk redirect rules.
+pub struct CloudflareLists {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareLists {
+    async fn call<R, T>(
+        &self,
+        method: Method,
+        path: String,
+        params: Option<QueryParams>,
+        body: Option<R>,
+    ) -> ApiResult<T>
+    where
+        R: Serialize + Clone,
+        T: DeserializeOwned,
+    {
+        self.c
+            .clone()
+            .perform_json(method, PerformOptions { path, params, body })
+            .await
+    }
+
+    fn path(account_id: &AccountId, rest: &str) -> String {
+        format!("accounts/{}/rules/lists{}", account_id, rest)
+    }
+
+    pub async fn list(&self, account_id: &AccountId) -> ApiResult<ApiResponse<Vec<List>>> {
+        let path = Self::path(account_id, "");
+        self.call::<(), _>(Method::GET, path, None, None).await
+    }
+
+    pub async fn get(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+    ) -> ApiResult<ApiResponse<List>> {
+        let path = Self::path(account_id, &format!("/{}", list_id));
+        self.call::<(), _>(Method::GET, path, None, None).await
+    }
+
+    pub async fn create(
+        &self,
+        account_id: &AccountId,
+        req: CreateListRequest,
+    ) -> ApiResult<ApiResponse<List>> {
+        let path = Self::path(account_id, "");
+        self.call(Method::POST, path, None, Some(req)).await
+    }
+
+    pub async fn update_description(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+        description: &str,
+    ) -> ApiResult<ApiResponse<List>> {
+        let path = Self::path(account_id, &format!("/{}", list_id));
+        let body = UpdateListRequest {
+            description: description.to_string(),
+        };
+        self.call(Method::PUT, path, None, Some(body)).await
+    }
+
+    /// Fails while the list is still referenced by a rule.
+    pub async fn delete(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+    ) -> ApiResult<ApiResponse<IdResult<ListId>>> {
+        let path = Self::path(account_id, &format!("/{}", list_id));
+        self.call::<(), _>(Method::DELETE, path, None, None).await
+    }
+
+    /// One page of items. Pass the previous page's [`ListItemsPage::next_cursor`] to continue;
+    /// `search` filters on the item values and comments.
+    pub async fn items(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+        cursor: Option<&str>,
+        search: Option<&str>,
+    ) -> ApiResult<ListItemsPage> {
+        let path = Self::path(account_id, &format!("/{}/items", list_id));
+        let mut params = vec![];
+        if let Some(cursor) = cursor {
+            params.push(("cursor".to_string(), cursor.to_string()));
+        }
+        if let Some(search) = search {
+            params.push(("search".to_string(), search.to_string()));
+        }
+        self.call::<(), _>(Method::GET, path, Some(params), None)
+            .await
+    }
+
+    pub async fn all_items(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+    ) -> ApiResult<Vec<ListItem>> {
+        let mut items = vec![];
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = self
+                .items(account_id, list_id, cursor.as_deref(), None)
+                .await?;
+            cursor = page.next_cursor().map(str::to_string);
+            items.extend(page.into_result()?);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    pub async fn get_item(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+        item_id: &ListItemId,
+    ) -> ApiResult<ApiResponse<ListItem>> {
+        let path = Self::path(account_id, &format!("/{}/items/{}", list_id, item_id));
+        self.call::<(), _>(Method::GET, path, None, None).await
+    }
+
+    /// Appends items. Poll the returned operation with [`Self::wait_for_operation`].
+    pub async fn add_items(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+        items: Vec<ListItemRequest>,
+    ) -> ApiResult<ApiResponse<ListOperation>> {
+        let path = Self::path(account_id, &format!("/{}/items", list_id));
+        self.call(Method::POST, path, None, Some(items)).await
+    }
+
+    /// Replaces every item of the list.
+    pub async fn replace_items(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+        items: Vec<ListItemRequest>,
+    ) -> ApiResult<ApiResponse<ListOperation>> {
+        let path = Self::path(account_id, &format!("/{}/items", list_id));
+        self.call(Method::PUT, path, None, Some(items)).await
+    }
+
+    pub async fn remove_items(
+        &self,
+        account_id: &AccountId,
+        list_id: &ListId,
+        item_ids: Vec<ListItemId>,
+    ) -> ApiResult<ApiResponse<ListOperation>> {
+        let path = Self::path(account_id, &format!("/{}/items", list_id));
+        let body = DeleteItemsRequest {
+            items: item_ids.into_iter().map(|id| IdResult { id }).collect(),
+        };
+        self.call(Method::DELETE, path, None, Some(body)).await
+    }
+
+    pub async fn operation(
+        &self,
+        account_id: &AccountId,
+        operation_id: &str,
+    ) -> ApiResult<ApiResponse<BulkOperation>> {
+        let path = Self::path(account_id, &format!("/bulk_operations/{}", operation_id));
+        self.call::<(), _>(Method::GET, path, None, None).await
+    }
+
+    /// Polls an operation every `interval` until it completes, fails, or `timeout` passes.
+    /// A failed operation is returned as an error carrying its message, and running out of
+    /// time as an [`ApiErrorKind::Timeout`] error.
+    pub async fn wait_for_operation(
+        &self,
+        account_id: &AccountId,
+        operation_id: &str,
+        interval: Duration,
+        timeout: Duration,
+    ) -> ApiResult<BulkOperation> {
+        poll_operation(operation_id, interval, timeout, || async {
+            self.operation(account_id, operation_id)
+                .await?
+                .into_result()
+        })
+        .await
+    }
+}
+
+async fn poll_operation<F, Fut>(
+    operation_id: &str,
+    interval: Duration,
+    timeout: Duration,
+    mut fetch: F,
+) -> ApiResult<BulkOperation>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ApiResult<BulkOperation>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let op = fetch().await?;
+        match op.status {
+            BulkOperationStatus::Completed => return Ok(op),
+            BulkOperationStatus::Failed => {
+                return Err(ApiError::new(format!(
+                    "list operation {} failed: {}",
+                    operation_id,
+                    op.error.as_deref().unwrap_or("no error given")
+                )))
+            }
+            _ if tokio::time::Instant::now() + interval > deadline => {
+                return Err(ApiError::with_kind(
+                    ApiErrorKind::Timeout,
+                    format!(
+                        "list operation {} still {:?} after {:?}",
+                        operation_id, op.status, timeout
+                    ),
+                ))
+            }
+            _ => tokio::time::sleep(interval).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_items_by_kind() {
+        let items = vec![
+            ListItemRequest::new("192.0.2.0/24".parse::<IpCidr>().unwrap()).with_comment("scanner"),
+            ListItemRequest::new(ListItemValue::Asn(64496)),
+            ListItemRequest::new(ListItemValue::Hostname(HostnameItem {
+                url_hostname: "*.example.com".to_string(),
+            })),
+        ];
+        assert_eq!(
+            serde_json::to_value(&items).unwrap(),
+            serde_json::json!([
+                {"ip": "192.0.2.0/24", "comment": "scanner"},
+                {"asn": 64496},
+                {"hostname": {"url_hostname": "*.example.com"}}
+            ])
+        );
+
+        let page: ListItemsPage = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "errors": [],
+            "messages": [],
+            "result": [{
+                "id": "2c0fc9fa937b11eaa1b71c4d701ab86e",
+                "ip": "2001:db8::1",
+                "created_on": "2024-05-01T12:00:00Z"
+            }],
+            "result_info": {"cursors": {"after": "yyy"}}
+        }))
+        .unwrap();
+        assert_eq!(page.next_cursor(), Some("yyy"));
+        let item = &page.into_result().unwrap()[0];
+        assert_eq!(
+            item.value,
+            ListItemValue::Ip("2001:db8::1".parse::<IpCidr>().unwrap())
+        );
+    }
+
+    fn op(status: BulkOperationStatus) -> BulkOperation {
+        BulkOperation {
+            id: "4da8780eeb215e6cb7f48dd981c4ea02".to_string(),
+            status,
+            error: None,
+            completed: None,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polls_until_the_operation_completes() {
+        let mut statuses = vec![
+            BulkOperationStatus::Pending,
+            BulkOperationStatus::Running,
+            BulkOperationStatus::Completed,
+        ]
+        .into_iter();
+        let start = tokio::time::Instant::now();
+        let done = poll_operation(
+            "op",
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            || {
+                let status = statuses.next().unwrap();
+                async move { Ok(op(status)) }
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(done.status, BulkOperationStatus::Completed);
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+
+        let failed = poll_operation(
+            "op",
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            || async {
+                Ok(BulkOperation {
+                    error: Some("invalid item".to_string()),
+                    ..op(BulkOperationStatus::Failed)
+                })
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(failed.kind(), ApiErrorKind::Api);
+        assert!(failed.to_string().contains("invalid item"), "{}", failed);
+    }
+
+    #[test]
+    fn keeps_unknown_kinds_and_statuses() {
+        let list: List = serde_json::from_value(serde_json::json!({
+            "id": "2c0fc9fa937b11eaa1b71c4d701ab86e",
+            "name": "future",
+            "kind": "email",
+            "operation_id": "4da8780eeb215e6cb7f48dd981c4ea02"
+        }))
+        .unwrap();
+        assert_eq!(list.kind, ListKind::Other("email".to_string()));
+        assert_eq!(serde_json::to_value(&list).unwrap()["kind"], "email");
+
+        let queued: BulkOperation = serde_json::from_value(serde_json::json!({
+            "id": "4da8780eeb215e6cb7f48dd981c4ea02",
+            "status": "queued"
+        }))
+        .unwrap();
+        assert_eq!(
+            queued.status,
+            BulkOperationStatus::Other("queued".to_string())
+        );
+        assert!(!queued.is_done());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_polling_through_unknown_statuses() {
+        let mut statuses = vec![
+            BulkOperationStatus::Other("queued".to_string()),
+            BulkOperationStatus::Other("validating".to_string()),
+            BulkOperationStatus::Completed,
+        ]
+        .into_iter();
+        let start = tokio::time::Instant::now();
+        let done = poll_operation(
+            "op",
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            || {
+                let status = statuses.next().unwrap();
+                async move { Ok(op(status)) }
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(done.status, BulkOperationStatus::Completed);
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_after_the_timeout() {
+        let mut polls = 0;
+        let start = tokio::time::Instant::now();
+        let err = poll_operation("op", Duration::from_secs(2), Duration::from_secs(5), || {
+            polls += 1;
+            async { Ok(op(BulkOperationStatus::Running)) }
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), ApiErrorKind::Timeout);
+        assert_eq!(polls, 3);
+        assert_eq!(start.elapsed(), Duration::from_secs(4));
+    }
+}
@@ -0,0 +1,439 @@
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use hyper::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    AccessRuleId, AccountId, ApiResponse, ApiResult, Cloudflare, ContentError, IdResult,
+    PerformOptions, QueryParams, Timestamp, V4PagePaginationArray, ZoneId,
+};
+
+pub mod lists;
+pub use lists::{
+    BulkOperation, BulkOperationStatus, CloudflareLists, CreateListRequest, HostnameItem, List,
+    ListCursorInfo, ListCursors, ListItem, ListItemRequest, ListItemValue, ListItemsPage, ListKind,
+    ListOperation, RedirectItem,
+};
+
+/// An IP address or network in CIDR notation. A bare address is a single-host network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpCidr {
+    pub addr: IpAddr,
+    pub prefix_len: u8,
+}
+
+impl IpCidr {
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, ContentError> {
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max {
+            return Err(ContentError(format!(
+                "prefix length {} too long for {}",
+                prefix_len, addr
+            )));
+        }
+        Ok(Self { addr, prefix_len })
+    }
+
+    pub fn is_host(&self) -> bool {
+        self.prefix_len == if self.addr.is_ipv4() { 32 } else { 128 }
+    }
+}
+
+impl From<IpAddr> for IpCidr {
+    fn from(addr: IpAddr) -> Self {
+        let prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        Self { addr, prefix_len }
+    }
+}
+
+impl Display for IpCidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_host() {
+            write!(f, "{}", self.addr)
+        } else {
+            write!(f, "{}/{}", self.addr, self.prefix_len)
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = ContentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ContentError(format!("invalid IP network: {}", s));
+        match s.trim().split_once('/') {
+            Some((addr, len)) => Self::new(
+                addr.parse().map_err(|_| invalid())?,
+                len.parse().map_err(|_| invalid())?,
+            ),
+            None => Ok(IpAddr::from_str(s.trim()).map_err(|_| invalid())?.into()),
+        }
+    }
+}
+
+impl Serialize for IpCidr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpCidr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// IP access rules live on a zone, an account (applying to all its zones) or the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessRuleScope {
+    Zone(ZoneId),
+    Account(AccountId),
+    User,
+}
+
+impl AccessRuleScope {
+    fn path(&self, rest: &str) -> String {
+        let prefix = match self {
+            Self::Zone(id) => format!("zones/{}", id),
+            Self::Account(id) => format!("accounts/{}", id),
+            Self::User => "user".to_string(),
+        };
+        format!("{}/firewall/access_rules/rules{}", prefix, rest)
+    }
+}
+
+impl From<ZoneId> for AccessRuleScope {
+    fn from(id: ZoneId) -> Self {
+        Self::Zone(id)
+    }
+}
+
+impl From<AccountId> for AccessRuleScope {
+    fn from(id: AccountId) -> Self {
+        Self::Account(id)
+    }
+}
+
+/// Modes this crate does not know yet come through as `Other`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessRuleMode {
+    Block,
+    Challenge,
+    JsChallenge,
+    ManagedChallenge,
+    /// Allows the request, skipping other security features.
+    Whitelist,
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl AccessRuleMode {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Block => "block",
+            Self::Challenge => "challenge",
+            Self::JsChallenge => "js_challenge",
+            Self::ManagedChallenge => "managed_challenge",
+            Self::Whitelist => "whitelist",
+            Self::Other(mode) => mode,
+        }
+    }
+}
+
+/// What an access rule matches, serialized as the API's `configuration` object.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "target", content = "value", rename_all = "snake_case")]
+pub enum AccessRuleTarget {
+    Ip(Ipv4Addr),
+    Ip6(Ipv6Addr),
+    /// Only /16 and /24 for IPv4, and /32, /48 and /64 for IPv6.
+    IpRange(IpCidr),
+    /// e.g. `AS12345`.
+    Asn(String),
+    /// Two-letter ISO 3166-1 code. Blocking a country needs an Enterprise plan.
+    Country(String),
+}
+
+impl AccessRuleTarget {
+    fn target(&self) -> &'static str {
+        match self {
+            Self::Ip(_) => "ip",
+            Self::Ip6(_) => "ip6",
+            Self::IpRange(_) => "ip_range",
+            Self::Asn(_) => "asn",
+            Self::Country(_) => "country",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            Self::Ip(ip) => ip.to_string(),
+            Self::Ip6(ip) => ip.to_string(),
+            Self::IpRange(net) => net.to_string(),
+            Self::Asn(v) | Self::Country(v) => v.clone(),
+        }
+    }
+}
+
+impl From<IpAddr> for AccessRuleTarget {
+    fn from(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => Self::Ip(ip),
+            IpAddr::V6(ip) => Self::Ip6(ip),
+        }
+    }
+}
+
+impl From<IpCidr> for AccessRuleTarget {
+    fn from(net: IpCidr) -> Self {
+        if net.is_host() {
+            net.addr.into()
+        } else {
+            Self::IpRange(net)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AccessRuleScopeInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// `user`, `organization`, `account` or `zone`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub scope_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccessRule {
+    pub id: AccessRuleId,
+    pub mode: AccessRuleMode,
+    pub configuration: AccessRuleTarget,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub allowed_modes: Vec<AccessRuleMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<AccessRuleScopeInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateAccessRuleRequest {
+    pub mode: AccessRuleMode,
+    pub configuration: AccessRuleTarget,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UpdateAccessRuleRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<AccessRuleMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ListAccessRulesRequest {
+    pub mode: Option<AccessRuleMode>,
+    pub target: Option<AccessRuleTarget>,
+    /// Matches rules whose notes contain this text.
+    pub notes: Option<String>,
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+impl ListAccessRulesRequest {
+    fn params(&self) -> QueryParams {
+        let mut params = vec![];
+        if let Some(mode) = &self.mode {
+            params.push(("mode".to_string(), mode.as_str().to_string()));
+        }
+        if let Some(target) = &self.target {
+            params.push((
+                "configuration.target".to_string(),
+                target.target().to_string(),
+            ));
+            params.push(("configuration.value".to_string(), target.value()));
+        }
+        if let Some(notes) = &self.notes {
+            params.push(("notes".to_string(), notes.clone()));
+        }
+        if let Some(page) = self.page {
+            params.push(("page".to_string(), page.to_string()));
+        }
+        if let Some(per_page) = self.per_page {
+            params.push(("per_page".to_string(), per_page.to_string()));
+        }
+        params
+    }
+}
+
+pub struct CloudflareAccessRules {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareAccessRules {
+    async fn call<R, T>(
+        &self,
+        method: Method,
+        path: String,
+        params: Option<QueryParams>,
+        body: Option<R>,
+    ) -> ApiResult<T>
+    where
+        R: Serialize + Clone,
+        T: DeserializeOwned,
+    {
+        self.c
+            .clone()
+            .perform_json(method, PerformOptions { path, params, body })
+            .await
+    }
+
+    pub async fn list(
+        &self,
+        scope: &AccessRuleScope,
+        req: &ListAccessRulesRequest,
+    ) -> ApiResult<V4PagePaginationArray<AccessRule>> {
+        self.call::<(), _>(Method::GET, scope.path(""), Some(req.params()), None)
+            .await
+    }
+
+    pub async fn create(
+        &self,
+        scope: &AccessRuleScope,
+        req: CreateAccessRuleRequest,
+    ) -> ApiResult<ApiResponse<AccessRule>> {
+        self.call(Method::POST, scope.path(""), None, Some(req))
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        scope: &AccessRuleScope,
+        rule_id: &AccessRuleId,
+        req: UpdateAccessRuleRequest,
+    ) -> ApiResult<ApiResponse<AccessRule>> {
+        let path = scope.path(&format!("/{}", rule_id));
+        self.call(Method::PATCH, path, None, Some(req)).await
+    }
+
+    pub async fn delete(
+        &self,
+        scope: &AccessRuleScope,
+        rule_id: &AccessRuleId,
+    ) -> ApiResult<ApiResponse<IdResult<AccessRuleId>>> {
+        let path = scope.path(&format!("/{}", rule_id));
+        self.call::<(), _>(Method::DELETE, path, None, None).await
+    }
+
+    /// Blocks a single address.
+    pub async fn block_ip(
+        &self,
+        scope: &AccessRuleScope,
+        ip: IpAddr,
+        notes: &str,
+    ) -> ApiResult<ApiResponse<AccessRule>> {
+        self.create(
+            scope,
+            CreateAccessRuleRequest {
+                mode: AccessRuleMode::Block,
+                configuration: ip.into(),
+                notes: Some(notes.to_string()),
+            },
+        )
+        .await
+    }
+
+    /// The rules matching exactly this target, across all pages.
+    pub async fn find(
+        &self,
+        scope: &AccessRuleScope,
+        target: AccessRuleTarget,
+    ) -> ApiResult<Vec<AccessRule>> {
+        let mut rules = vec![];
+        let mut req = ListAccessRulesRequest {
+            target: Some(target),
+            page: Some(1),
+            ..Default::default()
+        };
+        loop {
+            let mut res = self.list(scope, &req).await?;
+            let result_info = res.result_info.take();
+            let batch = res.into_result()?;
+            if batch.is_empty() {
+                break;
+            }
+            rules.extend(batch);
+            match result_info {
+                Some(info) if rules.len() < info.total_count => req.page = req.page.map(|p| p + 1),
+                _ => break,
+            }
+        }
+        Ok(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_networks_and_targets() {
+        let net: IpCidr = "198.51.100.0/24".parse().unwrap();
+        assert_eq!(net.to_string(), "198.51.100.0/24");
+        assert!("198.51.100.0/33".parse::<IpCidr>().is_err());
+        assert!("example.com".parse::<IpCidr>().is_err());
+
+        let host: IpCidr = "2001:db8::1/128".parse().unwrap();
+        assert_eq!(host.to_string(), "2001:db8::1");
+        assert_eq!(
+            AccessRuleTarget::from(host),
+            AccessRuleTarget::Ip6("2001:db8::1".parse().unwrap())
+        );
+
+        assert_eq!(
+            serde_json::to_value(AccessRuleTarget::from(net)).unwrap(),
+            serde_json::json!({"target": "ip_range", "value": "198.51.100.0/24"})
+        );
+        let rule: AccessRule = serde_json::from_value(serde_json::json!({
+            "id": "92f17202ed8bd63d69a66b86a49a8f6b",
+            "mode": "challenge",
+            "configuration": {"target": "ip", "value": "198.51.100.4"},
+            "notes": "abuse report",
+            "allowed_modes": ["whitelist", "block", "challenge"],
+            "scope": {"id": "023e105f4ecef8ad9ca31a8372d0c353", "type": "zone"}
+        }))
+        .unwrap();
+        assert_eq!(
+            rule.configuration,
+            AccessRuleTarget::Ip(Ipv4Addr::new(198, 51, 100, 4))
+        );
+        assert_eq!(rule.mode, AccessRuleMode::Challenge);
+    }
+
+    #[test]
+    fn keeps_unknown_modes() {
+        let mode: AccessRuleMode = serde_json::from_value(serde_json::json!("log")).unwrap();
+        assert_eq!(mode, AccessRuleMode::Other("log".to_string()));
+        assert_eq!(serde_json::to_value(&mode).unwrap(), "log");
+        let params = ListAccessRulesRequest {
+            mode: Some(mode),
+            ..Default::default()
+        }
+        .params();
+        assert_eq!(params, vec![("mode".to_string(), "log".to_string())]);
+    }
+}
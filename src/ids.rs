@@ -0,0 +1,149 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone)]
+pub struct IdError(String);
+
+impl Display for IdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IdError: {}", self.0)
+    }
+}
+
+impl std::error::Error for IdError {}
+
+fn validate_hex_id(kind: &str, s: &str) -> Result<String, IdError> {
+    if s.len() != 32 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(IdError(format!(
+            "{} must be 32 hexadecimal characters, got {:?}",
+            kind, s
+        )));
+    }
+    Ok(s.to_ascii_lowercase())
+}
+
+/// Declares a newtype around a 32 character hex identifier. The value is validated (and
+/// lowercased) whenever it is parsed or deserialized, and serializes as a plain string.
+macro_rules! hex_id {
+    ($name:ident) => {
+        #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[serde(try_from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                validate_hex_id(stringify!($name), s).map(Self)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = IdError;
+
+            fn try_from(s: String) -> Result<Self, Self::Error> {
+                Self::from_str(&s)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = IdError;
+
+            fn try_from(s: &str) -> Result<Self, Self::Error> {
+                Self::from_str(s)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+hex_id!(ZoneId);
+hex_id!(RecordId);
+hex_id!(AccountId);
+hex_id!(TsigId);
+hex_id!(PeerId);
+hex_id!(AclId);
+hex_id!(RulesetId);
+hex_id!(RuleId);
+hex_id!(AccessRuleId);
+hex_id!(ListId);
+hex_id!(ListItemId);
+hex_id!(PageRuleId);
+
+impl From<IdError> for crate::ApiError {
+    fn from(e: IdError) -> Self {
+        crate::ApiError::with_kind(crate::ApiErrorKind::Invalid, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "023e105f4ecef8ad9ca31a8372d0c353";
+
+    #[test]
+    fn rejects_wrong_lengths_and_non_hex() {
+        assert!(ZoneId::from_str(&ID[..31]).is_err());
+        assert!(ZoneId::from_str(&format!("{}0", ID)).is_err());
+        assert!(ZoneId::from_str("").is_err());
+        assert!(RecordId::from_str("023e105f4ecef8ad9ca31a8372d0c35g").is_err());
+        assert!(RecordId::from_str("023e105f-ecef-8ad9-ca31-a8372d0c").is_err());
+        // 32 bytes, but not 32 hex characters.
+        assert!(RecordId::from_str("023e105f4ecef8ad9ca31a8372d0cé3").is_err());
+        let err = ZoneId::try_from("nope").unwrap_err();
+        assert!(err.to_string().contains("ZoneId"), "{}", err);
+    }
+
+    #[test]
+    fn lowercases_mixed_case() {
+        let id = ZoneId::from_str("023E105F4ECEF8ad9ca31a8372D0C353").unwrap();
+        assert_eq!(id.as_str(), ID);
+        assert_eq!(id, ZoneId::from_str(ID).unwrap());
+    }
+
+    #[test]
+    fn round_trips_through_strings_and_serde() {
+        let zone: ZoneId = ID.parse().unwrap();
+        assert_eq!(zone.to_string(), ID);
+        assert_eq!(String::from(zone.clone()), ID);
+        assert_eq!(serde_json::to_value(&zone).unwrap(), serde_json::json!(ID));
+        assert_eq!(
+            serde_json::from_value::<ZoneId>(serde_json::json!(ID)).unwrap(),
+            zone
+        );
+
+        let record = RecordId::try_from(ID.to_ascii_uppercase()).unwrap();
+        assert_eq!(record.to_string(), ID);
+        assert_eq!(
+            serde_json::from_value::<RecordId>(serde_json::json!(ID.to_ascii_uppercase())).unwrap(),
+            record
+        );
+        assert!(serde_json::from_value::<RecordId>(serde_json::json!("short")).is_err());
+        assert!(serde_json::from_value::<RecordId>(serde_json::json!(42)).is_err());
+    }
+}
@@ -1,16 +1,39 @@
+pub mod cache;
+pub mod config;
 pub mod dns;
+pub mod firewall;
+pub mod ids;
+pub mod rate_limit;
+pub mod rulesets;
+pub mod secondary_dns;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod zones;
 
+pub use cache::*;
+pub use config::{ConfigError, Credentials};
 pub use dns::*;
-use log::{debug, error, info, warn};
+pub use firewall::*;
+pub use ids::*;
+use log::{debug, error, warn};
+pub use rate_limit::RateLimiter;
+pub use rulesets::*;
+pub use secondary_dns::*;
+pub use zones::*;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
 use url::Url;
 
 use http_body_util::{BodyExt, Full};
 use hyper::{
     body::{Bytes, Incoming},
-    Method, Request, Response, Uri,
+    Method, Request, Response, StatusCode, Uri,
 };
 use hyper_tls::HttpsConnector;
 use hyper_util::client::legacy::{connect::HttpConnector, Client};
@@ -22,15 +45,135 @@ pub enum Version {
     V3,
     V4,
 }
-impl ToString for Version {
-    fn to_string(&self) -> String {
-        match self {
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
             Self::V1 => "v1",
             Self::V2 => "v2",
             Self::V3 => "v3",
             Self::V4 => "v4",
+        };
+        write!(f, "{}", v)
+    }
+}
+
+/// Timestamp returned by the API, kept as the raw RFC 3339 string. With the `chrono` feature
+/// it can be parsed with [`Timestamp::parse`]; the type itself is the same either way.
+///
+/// Timestamps order by the instant they name, whatever their offset or precision, so records
+/// can be sorted by age with or without the feature. Strings that are not RFC 3339 sort first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[serde(transparent)]
+pub struct Timestamp(String);
+
+impl Timestamp {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Seconds and nanoseconds since the Unix epoch, if the string is RFC 3339.
+    fn instant(&self) -> Option<(i64, u32)> {
+        let s = self.0.as_bytes();
+        let num = |range: std::ops::Range<usize>| -> Option<i64> {
+            let part = s.get(range)?;
+            if part.is_empty() || !part.iter().all(u8::is_ascii_digit) {
+                return None;
+            }
+            std::str::from_utf8(part).ok()?.parse().ok()
+        };
+        let sep = |i: usize, allowed: &[u8]| s.get(i).is_some_and(|c| allowed.contains(c));
+        if !(sep(4, b"-") && sep(7, b"-") && sep(10, b"Tt ") && sep(13, b":") && sep(16, b":")) {
+            return None;
+        }
+        let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+        let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
         }
-        .to_string()
+        if hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+
+        let mut i = 19;
+        let mut nanos = 0u32;
+        if sep(i, b".") {
+            let digits = s[i + 1..].iter().take_while(|c| c.is_ascii_digit()).count();
+            if digits == 0 {
+                return None;
+            }
+            for (n, c) in s[i + 1..i + 1 + digits].iter().enumerate().take(9) {
+                nanos += u32::from(c - b'0') * 10u32.pow(8 - n as u32);
+            }
+            i += 1 + digits;
+        }
+        let offset = match s.get(i..)? {
+            b"Z" | b"z" => 0,
+            [sign @ (b'+' | b'-'), ..] if s.len() == i + 6 && sep(i + 3, b":") => {
+                let offset = num(i + 1..i + 3)? * 3600 + num(i + 4..i + 6)? * 60;
+                if *sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+            _ => return None,
+        };
+
+        // Days from 1970-01-01 in the proleptic Gregorian calendar.
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146097 + doe - 719468;
+        let secs = days * 86400 + hour * 3600 + minute * 60 + second - offset;
+        Some((secs, nanos))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn parse(&self) -> Result<chrono::DateTime<chrono::Utc>, chrono::ParseError> {
+        chrono::DateTime::parse_from_rfc3339(&self.0).map(|t| t.with_timezone(&chrono::Utc))
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Ord for Timestamp {
+    /// By instant, then by the raw string so that equal instants written differently still
+    /// compare consistently with `Eq`.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.instant()
+            .cmp(&other.instant())
+            .then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<String> for Timestamp {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&str> for Timestamp {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Timestamp {
+    fn from(t: chrono::DateTime<chrono::Utc>) -> Self {
+        Self(t.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
     }
 }
 
@@ -51,21 +194,79 @@ pub struct V4PagePaginationArray<T> {
     pub result_info: Option<ResultInfo>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiErrorKind {
+    /// The API rejected the request for a reason not covered by a more specific kind.
+    #[default]
+    Api,
+    Auth,
+    NotFound,
+    RateLimited,
+    /// The request never got a response.
+    Transport,
+    /// The response body could not be decoded.
+    Decode,
+    /// The request could not be built from the given input.
+    Invalid,
+    /// An asynchronous operation was still running when the wait for it ran out.
+    Timeout,
+}
+
+impl ApiErrorKind {
+    fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Auth,
+            StatusCode::NOT_FOUND => Self::NotFound,
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited,
+            _ => Self::Decode,
+        }
+    }
+
+    fn from_code(code: usize) -> Self {
+        match code {
+            6003 | 6111 | 9103 | 9106 | 9109 | 10000 | 10001 => Self::Auth,
+            1001 | 7000 | 7003 | 81044 => Self::NotFound,
+            971 | 10429 => Self::RateLimited,
+            _ => Self::Api,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ApiError(String);
+pub struct ApiError {
+    kind: ApiErrorKind,
+    message: String,
+}
 
 impl ApiError {
     pub fn new(msg: impl ToString) -> Self {
-        Self(msg.to_string())
+        Self::with_kind(ApiErrorKind::Api, msg)
+    }
+
+    pub fn with_kind(kind: ApiErrorKind, msg: impl ToString) -> Self {
+        Self {
+            kind,
+            message: msg.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> ApiErrorKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
     }
 }
 
 impl Display for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "ApiError:{}", self.0)
+        write!(f, "ApiError:{}", self.message)
     }
 }
 
+impl std::error::Error for ApiError {}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ResponseInfo {
     pub code: usize,
@@ -81,6 +282,13 @@ pub struct Cloudflare {
 
     email: String,
     auth_key: String,
+    api_key: String,
+    account_id: Option<AccountId>,
+
+    zone_cache: Option<Arc<RwLock<ZoneCache>>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    #[cfg(feature = "testing")]
+    cassette: Option<Arc<testing::Cassette>>,
 }
 
 impl Default for Cloudflare {
@@ -90,17 +298,27 @@ impl Default for Cloudflare {
             base_url: Url::parse("https://api.cloudflare.com").unwrap(),
             email: "".to_string(),
             auth_key: "".to_string(),
+            api_key: "".to_string(),
+            account_id: None,
+            zone_cache: None,
+            rate_limiter: None,
+            #[cfg(feature = "testing")]
+            cassette: None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TokenStatusResult {
     pub id: String,
     pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_on: Option<Timestamp>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TokenStatus {
     pub result: TokenStatusResult,
 }
@@ -119,33 +337,184 @@ pub struct ApiResponse<T> {
     pub result_info: Option<ResultInfo>,
 }
 
+fn error_from_infos(errors: &[ResponseInfo]) -> ApiError {
+    let msgs: Vec<String> = errors
+        .iter()
+        .map(|e| format!("{}: {}", e.code, e.message))
+        .collect();
+    let kind = errors
+        .first()
+        .map(|e| ApiErrorKind::from_code(e.code))
+        .unwrap_or_default();
+    ApiError::with_kind(kind, msgs.join(", "))
+}
+
+impl<T> V4PagePaginationArray<T> {
+    /// Like [`ApiResponse::into_result`], returning this page's items.
+    pub fn into_result(self) -> ApiResult<Vec<T>> {
+        if !self.success {
+            return Err(error_from_infos(&self.errors.unwrap_or_default()));
+        }
+        Ok(self.result.unwrap_or_default())
+    }
+}
+
+impl<T> ApiResponse<T> {
+    /// Unwraps the envelope, turning `success: false` or a missing `result` into an [`ApiError`]
+    /// carrying the API's error messages.
+    pub fn into_result(self) -> ApiResult<T> {
+        if !self.success {
+            return Err(error_from_infos(&self.errors));
+        }
+        match self.result {
+            Some(r) => Ok(r),
+            None => Err(ApiError::new("response contained no result")),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordIdResult {
+    pub id: RecordId,
+}
+
+/// One page of [`CloudflareDns::list_records`]. Build it with [`ListRecordsRequest::new`]
+/// so fields added later keep their defaults.
+#[derive(Debug, Clone)]
 pub struct ListRecordsRequest {
-    pub zone_id: String,
+    pub zone_id: ZoneId,
     pub page: usize,
+    pub filter: RecordFilter,
+}
+
+impl ListRecordsRequest {
+    /// The first page of every record in the zone.
+    pub fn new(zone_id: ZoneId) -> Self {
+        Self {
+            zone_id,
+            page: 1,
+            filter: RecordFilter::default(),
+        }
+    }
+
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = page;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: RecordFilter) -> Self {
+        self.filter = filter;
+        self
+    }
 }
 
 impl CloudflareDns {
     pub async fn list_records(
         &self,
-        ListRecordsRequest { zone_id, page }: ListRecordsRequest,
+        ListRecordsRequest {
+            zone_id,
+            page,
+            filter,
+        }: ListRecordsRequest,
     ) -> ApiResult<ListDnsRecordsResponse> {
+        let mut params = filter.params();
+        params.push(("page".to_string(), page.to_string()));
         self.c
             .clone()
             .perform_json(
                 Method::GET,
                 PerformOptions::<()> {
                     path: format!("zones/{}/dns_records", zone_id),
-                    params: None,
+                    params: Some(params),
                     body: None,
                 },
             )
             .await
     }
 
+    pub fn analytics(&self) -> CloudflareDnsAnalytics {
+        CloudflareDnsAnalytics { c: self.c.clone() }
+    }
+
+    pub fn scan(&self) -> CloudflareDnsScan {
+        CloudflareDnsScan { c: self.c.clone() }
+    }
+
+    pub fn settings(&self) -> CloudflareDnsSettings {
+        CloudflareDnsSettings { c: self.c.clone() }
+    }
+
+    pub fn dnssec(&self) -> CloudflareDnssec {
+        CloudflareDnssec { c: self.c.clone() }
+    }
+
+    pub fn ddns(&self, zone_id: ZoneId, name: &str) -> DdnsUpdater {
+        DdnsUpdater::new(&self.c, zone_id, name)
+    }
+
+    pub fn bulk(&self) -> BulkExecutor {
+        BulkExecutor::new(&self.c)
+    }
+
+    pub fn batch(&self, zone_id: ZoneId) -> BatchBuilder {
+        BatchBuilder::new(&self.c, zone_id)
+    }
+
+    pub fn reconciler(&self, zone_id: ZoneId) -> Reconciler {
+        Reconciler::new(&self.c, zone_id)
+    }
+
+    /// Pages through `list_records` until every record in the zone has been fetched.
+    pub async fn list_all_records(&self, zone_id: &ZoneId) -> ApiResult<Vec<RecordMessage>> {
+        self.list_all_records_matching(zone_id, &RecordFilter::default())
+            .await
+    }
+
+    /// Records carrying `tag`, given as `name:value` for an exact match or as a bare name for
+    /// any value.
+    pub async fn records_owned_by(
+        &self,
+        zone_id: &ZoneId,
+        tag: &str,
+    ) -> ApiResult<Vec<RecordMessage>> {
+        let filter = RecordFilter::new().with_tag(TagFilter::from_tag(tag));
+        self.list_all_records_matching(zone_id, &filter).await
+    }
+
+    /// Like [`CloudflareDns::list_all_records`], keeping only records that match `filter`.
+    pub async fn list_all_records_matching(
+        &self,
+        zone_id: &ZoneId,
+        filter: &RecordFilter,
+    ) -> ApiResult<Vec<RecordMessage>> {
+        let mut records = vec![];
+        let mut page = 1;
+        loop {
+            let mut res = self
+                .list_records(
+                    ListRecordsRequest::new(zone_id.clone())
+                        .with_page(page)
+                        .with_filter(filter.clone()),
+                )
+                .await?;
+            let result_info = res.result_info.take();
+            let batch = res.into_result()?;
+            if batch.is_empty() {
+                break;
+            }
+            records.extend(batch);
+            match result_info {
+                Some(info) if records.len() < info.total_count => page += 1,
+                _ => break,
+            }
+        }
+        Ok(records)
+    }
+
     pub async fn get_record(
         &self,
-        zone_id: &str,
-        record_id: &str,
+        zone_id: &ZoneId,
+        record_id: &RecordId,
     ) -> ApiResult<ApiResponse<RecordMessage>> {
         self.c
             .clone()
@@ -159,24 +528,154 @@ impl CloudflareDns {
             )
             .await
     }
-    pub async fn overwrite_record(
-        self,
-        zone_id: &str,
-        record_id: &str,
-        record: impl ToRecordMessage,
+    pub async fn create_record(
+        &self,
+        zone_id: &ZoneId,
+        record: impl Into<RecordRequest>,
+    ) -> ApiResult<ApiResponse<RecordMessage>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::POST,
+                PerformOptions {
+                    body: Some(record.into()),
+                    path: format!("zones/{}/dns_records", zone_id),
+                    params: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn replace_record(
+        &self,
+        zone_id: &ZoneId,
+        record_id: &RecordId,
+        record: impl Into<RecordRequest>,
+    ) -> ApiResult<ApiResponse<RecordMessage>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::PUT,
+                PerformOptions {
+                    body: Some(record.into()),
+                    path: format!("zones/{}/dns_records/{}", zone_id, record_id),
+                    params: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn delete_record(
+        &self,
+        zone_id: &ZoneId,
+        record_id: &RecordId,
+    ) -> ApiResult<ApiResponse<RecordIdResult>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::DELETE,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/dns_records/{}", zone_id, record_id),
+                    body: None,
+                    params: None,
+                },
+            )
+            .await
+    }
+
+    /// Downloads the zone's records as a BIND zone file.
+    pub async fn export_records(&self, zone_id: &ZoneId) -> ApiResult<String> {
+        let (status, body) = self
+            .c
+            .clone()
+            .perform_raw(
+                Method::GET,
+                &format!("zones/{}/dns_records/export", zone_id),
+                None,
+                None,
+                None,
+            )
+            .await?;
+        if !status.is_success() {
+            return Err(ApiError::with_kind(
+                ApiErrorKind::from_status(status),
+                format!("export failed with {}: {}", status, body),
+            ));
+        }
+        Ok(body)
+    }
+
+    /// Uploads a BIND zone file. `proxied` sets the default proxy status for records whose line
+    /// carries no `cf_tags=cf-proxied:<bool>` annotation.
+    pub async fn import_records(
+        &self,
+        zone_id: &ZoneId,
+        zone_file: &str,
+        proxied: bool,
+    ) -> ApiResult<ApiResponse<ImportRecordsResult>> {
+        let boundary = format!(
+            "cloudflare-rs-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let body = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"zone.txt\"\r\nContent-Type: text/plain\r\n\r\n{file}\r\n--{b}\r\nContent-Disposition: form-data; name=\"proxied\"\r\n\r\n{proxied}\r\n--{b}--\r\n",
+            b = boundary,
+            file = zone_file,
+            proxied = proxied,
+        );
+        let content_type = format!("multipart/form-data; boundary={}", boundary);
+        let (status, res) = self
+            .c
+            .clone()
+            .perform_raw(
+                Method::POST,
+                &format!("zones/{}/dns_records/import", zone_id),
+                None,
+                Some(&content_type),
+                Some(Bytes::from(body)),
+            )
+            .await?;
+        match serde_json::from_str(&res) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(ApiError::with_kind(
+                ApiErrorKind::from_status(status),
+                format!("error parsing json:{}", e),
+            )),
+        }
+    }
+
+    pub async fn update_record(
+        &self,
+        zone_id: &ZoneId,
+        record_id: &RecordId,
+        record: impl Into<RecordRequest>,
     ) -> ApiResult<ApiResponse<RecordMessage>> {
-        let record = record.to_record_message().for_update();
         self.c
+            .clone()
             .perform_json(
                 Method::PATCH,
                 PerformOptions {
-                    body: Some(record),
+                    body: Some(record.into()),
                     path: format!("zones/{}/dns_records/{}", zone_id, record_id),
                     params: None,
                 },
             )
             .await
     }
+
+    /// Like [`CloudflareDns::update_record`], taking any typed record.
+    pub async fn overwrite_record(
+        self,
+        zone_id: &ZoneId,
+        record_id: &RecordId,
+        record: impl ToRecordMessage,
+    ) -> ApiResult<ApiResponse<RecordMessage>> {
+        let record = record.to_record_message().for_update();
+        self.update_record(zone_id, record_id, record).await
+    }
 }
 
 impl Cloudflare {
@@ -193,22 +692,136 @@ impl Cloudflare {
         s
     }
 
-    fn bearer_token(&self) -> impl ToString {
-        format!("Bearer {}", self.auth_key)
+    /// Global API key, sent with the address set by [`Cloudflare::with_email`]. Only used when
+    /// no token is set.
+    pub fn with_api_key(self, key: &str) -> Self {
+        let mut s = self.clone();
+        s.api_key = key.to_string();
+        s
+    }
+
+    /// Points the client at another API host, such as [`testing::FakeCloudflare`].
+    pub fn with_base_url(self, base_url: Url) -> Self {
+        let mut s = self.clone();
+        s.base_url = base_url;
+        s
+    }
+
+    /// Routes every request through `cassette`, recording or replaying it.
+    #[cfg(feature = "testing")]
+    pub fn with_cassette(self, cassette: Arc<testing::Cassette>) -> Self {
+        let mut s = self.clone();
+        s.cassette = Some(cassette);
+        s
+    }
+
+    /// Default account for account-scoped calls.
+    pub fn with_account_id(self, account_id: AccountId) -> Self {
+        let mut s = self.clone();
+        s.account_id = Some(account_id);
+        s
+    }
+
+    pub fn account_id(&self) -> Option<&AccountId> {
+        self.account_id.as_ref()
+    }
+
+    /// Remembers the zone name to id mappings found by [`CloudflareZones::resolve_zone`], and
+    /// the names it found not to be zones. The cache is shared by every clone of the client
+    /// made after this call.
+    pub fn with_zone_cache(self) -> Self {
+        let mut s = self.clone();
+        s.zone_cache = Some(Arc::new(RwLock::new(HashMap::new())));
+        s
+    }
+
+    /// Throttles every request made through this client, and its clones, to `requests` per
+    /// `per`.
+    pub fn with_rate_limit(self, requests: u32, per: std::time::Duration) -> Self {
+        let mut s = self.clone();
+        s.rate_limiter = Some(Arc::new(RateLimiter::new(requests, per)));
+        s
+    }
+
+    /// `None` when `name` has not been looked up, `Some(None)` when it is known not to be a zone.
+    fn cached_zone_id(&self, name: &str) -> Option<Option<ZoneId>> {
+        let cache = self.zone_cache.as_ref()?;
+        let cache = cache.read().ok()?;
+        cache.get(name).cloned()
+    }
+
+    fn cache_zone_id(&self, name: &str, id: Option<&ZoneId>) {
+        if let Some(cache) = &self.zone_cache {
+            if let Ok(mut cache) = cache.write() {
+                cache.insert(name.to_string(), id.cloned());
+            }
+        }
+    }
+
+    fn auth_headers(&self) -> Vec<(&'static str, String)> {
+        if self.auth_key.is_empty() && !self.api_key.is_empty() {
+            vec![
+                ("X-Auth-Email", self.email.clone()),
+                ("X-Auth-Key", self.api_key.clone()),
+            ]
+        } else {
+            vec![("Authorization", format!("Bearer {}", self.auth_key))]
+        }
     }
 
     pub async fn verify_token(self) -> ApiResult<TokenStatus> {
-        Ok(Default::default())
+        let res: ApiResponse<TokenStatusResult> = self
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: "user/tokens/verify".to_string(),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await?;
+        Ok(TokenStatus {
+            result: res.into_result()?,
+        })
     }
 
     pub fn dns(&self) -> CloudflareDns {
         CloudflareDns { c: self.clone() }
     }
 
+    pub fn cache(&self) -> CloudflareCache {
+        CloudflareCache {
+            c: self.clone(),
+            chunk_size: DEFAULT_PURGE_CHUNK_SIZE,
+        }
+    }
+
+    pub fn access_rules(&self) -> CloudflareAccessRules {
+        CloudflareAccessRules { c: self.clone() }
+    }
+
+    pub fn lists(&self) -> CloudflareLists {
+        CloudflareLists { c: self.clone() }
+    }
+
+    pub fn rulesets(&self) -> CloudflareRulesets {
+        CloudflareRulesets { c: self.clone() }
+    }
+
+    pub fn zones(&self) -> CloudflareZones {
+        CloudflareZones { c: self.clone() }
+    }
+
+    pub fn secondary_dns(&self) -> CloudflareSecondaryDns {
+        CloudflareSecondaryDns { c: self.clone() }
+    }
+
     async fn perform(
         self,
         method: Method,
         path: &str,
+        params: Option<QueryParams>,
+        content_type: Option<&str>,
         body: Option<Bytes>,
     ) -> ApiResult<Response<Incoming>> {
         let req_body = match body {
@@ -216,19 +829,26 @@ impl Cloudflare {
             None => Bytes::new(),
         };
 
-        let tok = self.bearer_token().to_string().clone();
-        let req = match Request::builder()
-            .uri(self.url(path)?)
-            .method(method)
-            .header("Authorization", tok)
-            .body(Full::new(req_body))
-        {
+        let mut builder = Request::builder()
+            .uri(self.url(path, params)?)
+            .method(method);
+        for (name, value) in self.auth_headers() {
+            builder = builder.header(name, value);
+        }
+        if let Some(content_type) = content_type {
+            builder = builder.header("Content-Type", content_type);
+        }
+        let req = match builder.body(Full::new(req_body)) {
             Ok(r) => r,
-            Err(e) => return Err(ApiError(format!("{}", e))),
+            Err(e) => return Err(ApiError::with_kind(ApiErrorKind::Invalid, e)),
         };
 
         debug!("REQUESTT:{:?}", req);
 
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+
         let client: Client<HttpsConnector<HttpConnector>, Full<Bytes>> =
             Client::builder(hyper_util::rt::TokioExecutor::new()).build(HttpsConnector::new());
 
@@ -239,14 +859,14 @@ impl Cloudflare {
             }
             Err(e) => {
                 error!("failed to send request:{}", e);
-                return Err(ApiError(format!("{}", e)));
+                return Err(ApiError::with_kind(ApiErrorKind::Transport, e));
             }
         };
 
         Ok(res)
     }
 
-    fn url(self, path: &str) -> Result<Uri, ApiError> {
+    fn url(&self, path: &str, params: Option<QueryParams>) -> Result<Uri, ApiError> {
         debug!("getting uri");
         let base = self
             .base_url
@@ -254,28 +874,51 @@ impl Cloudflare {
             .to_string()
             .trim_end_matches("/")
             .to_string();
-        let path = path.trim_start_matches("/");
-        let uri = format!("{}/client/{}/{}", base, self.version.to_string(), path);
+        let uri = format!(
+            "{}/client/{}/{}",
+            base,
+            self.version,
+            relative_uri(path, params)
+        );
         debug!("URI:{}", uri);
         let res = match Uri::from_str(&uri) {
             Ok(u) => Ok(u),
-            Err(e) => Err(ApiError(format!("{}", e))),
+            Err(e) => Err(ApiError::with_kind(ApiErrorKind::Invalid, e)),
         };
         debug!("{:?}", res.clone());
         res
     }
 
-    async fn perform_json<R, T>(self, method: Method, opts: PerformOptions<R>) -> ApiResult<T>
-    where
-        R: Serialize + Clone,
-        T: DeserializeOwned,
-    {
-        let body = match opts.body {
-            Some(bod) => Some(Bytes::from(serde_json::to_string(&bod).unwrap_or_default())),
-            None => None,
-        };
-        debug!("request body:{:?}", body);
-        let result = self.perform(method, &opts.path, body).await?;
+    async fn perform_raw(
+        self,
+        method: Method,
+        path: &str,
+        params: Option<QueryParams>,
+        content_type: Option<&str>,
+        body: Option<Bytes>,
+    ) -> ApiResult<(StatusCode, String)> {
+        #[cfg(feature = "testing")]
+        if let Some(cassette) = self.cassette.clone() {
+            return cassette
+                .perform(self, method, path, params, content_type, body)
+                .await;
+        }
+        self.send_raw(method, path, params, content_type, body)
+            .await
+    }
+
+    async fn send_raw(
+        self,
+        method: Method,
+        path: &str,
+        params: Option<QueryParams>,
+        content_type: Option<&str>,
+        body: Option<Bytes>,
+    ) -> ApiResult<(StatusCode, String)> {
+        let result = self
+            .perform(method, path, params, content_type, body)
+            .await?;
+        let status = result.status();
 
         let bytes = String::from_utf8(
             result
@@ -287,11 +930,31 @@ impl Cloudflare {
         )
         .unwrap_or_default();
         debug!("raw_body:{}", bytes);
+        Ok((status, bytes))
+    }
+
+    async fn perform_json<R, T>(self, method: Method, opts: PerformOptions<R>) -> ApiResult<T>
+    where
+        R: Serialize + Clone,
+        T: DeserializeOwned,
+    {
+        let body = opts
+            .body
+            .map(|bod| Bytes::from(serde_json::to_string(&bod).unwrap_or_default()));
+        debug!("request body:{:?}", body);
+        let content_type = body.as_ref().map(|_| "application/json");
+        let (status, bytes) = self
+            .perform_raw(method, &opts.path, opts.params, content_type, body)
+            .await?;
+
         let de: T = match serde_json::from_str(&bytes) {
             Ok(v) => v,
             Err(e) => {
                 warn!("error parsing json:{}", e);
-                return Err(ApiError(format!("error parsing json:{}", e)));
+                return Err(ApiError::with_kind(
+                    ApiErrorKind::from_status(status),
+                    format!("error parsing json:{}", e),
+                ));
             }
         };
 
@@ -299,12 +962,116 @@ impl Cloudflare {
     }
 }
 
+/// Zone name to id, with `None` for names known not to be zones.
+type ZoneCache = HashMap<String, Option<ZoneId>>;
+
+/// Query string pairs. A key may repeat, e.g. for several `tag.*` filters.
+type QueryParams = Vec<(String, String)>;
+
+/// `path` with the query string built from `params`, sorted so equal requests always
+/// produce the same URI.
+fn relative_uri(path: &str, params: Option<QueryParams>) -> String {
+    let path = path.trim_start_matches("/");
+    let Some(mut params) = params else {
+        return path.to_string();
+    };
+    params.sort();
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(params)
+        .finish();
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, query)
+    }
+}
+
 #[derive(Clone)]
 struct PerformOptions<T>
 where
     T: Serialize + Clone,
 {
     path: String,
-    params: Option<HashMap<String, String>>,
+    params: Option<QueryParams>,
     body: Option<T>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_keep_the_raw_string() {
+        let rec: RecordMessage = serde_json::from_value(serde_json::json!({
+            "type": "A",
+            "name": "www.example.com",
+            "content": "192.0.2.1",
+            "created_on": "2024-05-01T12:00:00.123456Z"
+        }))
+        .unwrap();
+        let ts = rec.created_on().unwrap();
+        assert_eq!(ts.as_str(), "2024-05-01T12:00:00.123456Z");
+        assert_eq!(
+            serde_json::to_value(ts).unwrap(),
+            serde_json::json!("2024-05-01T12:00:00.123456Z")
+        );
+
+        #[cfg(feature = "chrono")]
+        {
+            use chrono::TimeZone;
+            let parsed = ts.parse().unwrap();
+            assert_eq!(
+                parsed.timestamp(),
+                chrono::Utc
+                    .with_ymd_and_hms(2024, 5, 1, 12, 0, 0)
+                    .unwrap()
+                    .timestamp()
+            );
+            assert_eq!(Timestamp::from(parsed), *ts);
+        }
+    }
+
+    #[test]
+    fn timestamps_order_by_instant() {
+        let ts = |s: &str| Timestamp::from(s);
+        assert!(ts("2024-05-01T12:00:00Z") < ts("2024-05-01T12:00:00.5Z"));
+        assert!(ts("2024-05-01T12:00:00.25Z") < ts("2024-05-01T12:00:00.3Z"));
+        assert!(ts("2024-05-01T12:30:00+01:00") < ts("2024-05-01T12:00:00Z"));
+        assert!(ts("2024-05-01T11:30:00-01:00") > ts("2024-05-01T12:00:00Z"));
+        assert!(ts("1969-12-31T23:59:59Z") < ts("1970-01-01T00:00:00Z"));
+        assert!(ts("not a time") < ts("1900-01-01T00:00:00Z"));
+
+        let mut sorted = vec![
+            ts("2019-10-24T17:09:42.883908+01:00"),
+            ts("2019-10-24T16:09:42Z"),
+            ts("2019-10-24T16:09:43Z"),
+        ];
+        sorted.sort();
+        assert_eq!(
+            sorted,
+            vec![
+                ts("2019-10-24T16:09:42Z"),
+                ts("2019-10-24T17:09:42.883908+01:00"),
+                ts("2019-10-24T16:09:43Z"),
+            ]
+        );
+        assert_ne!(ts("2024-05-01T12:00:00Z"), ts("2024-05-01T13:00:00+01:00"));
+        assert_eq!(
+            ts("2024-05-01T12:00:00Z").instant(),
+            ts("2024-05-01T13:00:00+01:00").instant()
+        );
+        assert_eq!(
+            ts("1970-01-02T00:00:01.5Z").instant(),
+            Some((86401, 500_000_000))
+        );
+
+        #[cfg(feature = "chrono")]
+        for t in &sorted {
+            let parsed = t.parse().unwrap();
+            assert_eq!(
+                t.instant(),
+                Some((parsed.timestamp(), parsed.timestamp_subsec_nanos()))
+            );
+        }
+    }
+}
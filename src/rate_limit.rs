@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use tokio::{sync::Mutex, time::Instant};
+
+/// Token bucket shared by every clone of a [`crate::Cloudflare`] client. Cloudflare allows
+/// 1200 requests per five minutes per user, see [`RateLimiter::cloudflare_default`].
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    per_second: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    pub fn new(requests: u32, per: Duration) -> Self {
+        let capacity = requests.max(1) as f64;
+        Self {
+            capacity,
+            per_second: capacity / per.as_secs_f64().max(f64::EPSILON),
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    pub fn cloudflare_default() -> Self {
+        Self::new(1200, Duration::from_secs(300))
+    }
+
+    /// Waits until a request may be sent.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.1).as_secs_f64();
+                state.0 = (state.0 + elapsed * self.per_second).min(self.capacity);
+                state.1 = now;
+                if state.0 >= 1.0 {
+                    state.0 -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.0) / self.per_second)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn paces_requests_after_the_burst() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(1));
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        limiter.acquire().await;
+        let third = start.elapsed();
+        assert!(third >= Duration::from_millis(500), "{:?}", third);
+        assert!(third < Duration::from_millis(510), "{:?}", third);
+
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let idle = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(idle.elapsed(), Duration::ZERO);
+        limiter.acquire().await;
+        assert!(idle.elapsed() >= Duration::from_millis(500));
+    }
+}
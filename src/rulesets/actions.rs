@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use super::RulesetPhase;
+use crate::{RuleId, RulesetId, SecurityLevel, SslMode};
+
+/// What a rule does when its expression matches, together with the action's parameters.
+///
+/// Serialized as the API's `action` and `action_parameters` fields. Actions this crate has no
+/// variant for, or whose parameters carry fields the typed form lacks at any depth, come
+/// through as `Other` so that they are sent back unchanged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "RawRuleAction", into = "RawRuleAction")]
+pub enum RuleAction {
+    Block(BlockParameters),
+    Challenge,
+    JsChallenge,
+    ManagedChallenge,
+    Log,
+    /// Runs another ruleset, typically a managed one.
+    Execute(ExecuteParameters),
+    Skip(SkipParameters),
+    /// Transform rules: URL rewrites and header modifications.
+    Rewrite(RewriteParameters),
+    Redirect(RedirectParameters),
+    /// Origin rules.
+    Route(RouteParameters),
+    /// Cache rules.
+    SetCacheSettings(SetCacheSettingsParameters),
+    /// Configuration rules.
+    SetConfig(SetConfigParameters),
+    CompressResponse(CompressResponseParameters),
+    ServeError(ServeErrorParameters),
+    Other {
+        action: String,
+        action_parameters: Option<Value>,
+    },
+}
+
+impl RuleAction {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Block(_) => "block",
+            Self::Challenge => "challenge",
+            Self::JsChallenge => "js_challenge",
+            Self::ManagedChallenge => "managed_challenge",
+            Self::Log => "log",
+            Self::Execute(_) => "execute",
+            Self::Skip(_) => "skip",
+            Self::Rewrite(_) => "rewrite",
+            Self::Redirect(_) => "redirect",
+            Self::Route(_) => "route",
+            Self::SetCacheSettings(_) => "set_cache_settings",
+            Self::SetConfig(_) => "set_config",
+            Self::CompressResponse(_) => "compress_response",
+            Self::ServeError(_) => "serve_error",
+            Self::Other { action, .. } => action,
+        }
+    }
+
+    fn parameters(&self) -> Option<Value> {
+        fn to_value<P: Serialize>(p: &P) -> Option<Value> {
+            serde_json::to_value(p)
+                .ok()
+                .filter(|v| v.as_object().is_none_or(|o| !o.is_empty()))
+        }
+        match self {
+            Self::Challenge | Self::JsChallenge | Self::ManagedChallenge | Self::Log => None,
+            Self::Block(p) => to_value(p),
+            Self::Execute(p) => to_value(p),
+            Self::Skip(p) => to_value(p),
+            Self::Rewrite(p) => to_value(p),
+            Self::Redirect(p) => to_value(p),
+            Self::Route(p) => to_value(p),
+            Self::SetCacheSettings(p) => to_value(p),
+            Self::SetConfig(p) => to_value(p),
+            Self::CompressResponse(p) => to_value(p),
+            Self::ServeError(p) => to_value(p),
+            Self::Other {
+                action_parameters, ..
+            } => action_parameters.clone(),
+        }
+    }
+}
+
+/// The wire form of [`RuleAction`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct RawRuleAction {
+    action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    action_parameters: Option<Value>,
+}
+
+impl From<RuleAction> for RawRuleAction {
+    fn from(action: RuleAction) -> Self {
+        Self {
+            action: action.name().to_string(),
+            action_parameters: action.parameters(),
+        }
+    }
+}
+
+impl From<RawRuleAction> for RuleAction {
+    fn from(raw: RawRuleAction) -> Self {
+        fn typed<P: DeserializeOwned>(
+            params: &Option<Value>,
+            f: fn(P) -> RuleAction,
+        ) -> Option<RuleAction> {
+            let params = params
+                .clone()
+                .unwrap_or_else(|| Value::Object(Default::default()));
+            serde_json::from_value(params).ok().map(f)
+        }
+        let p = &raw.action_parameters;
+        let action = match raw.action.as_str() {
+            "block" => typed(p, Self::Block),
+            "challenge" => Some(Self::Challenge),
+            "js_challenge" => Some(Self::JsChallenge),
+            "managed_challenge" => Some(Self::ManagedChallenge),
+            "log" => Some(Self::Log),
+            "execute" => typed(p, Self::Execute),
+            "skip" => typed(p, Self::Skip),
+            "rewrite" => typed(p, Self::Rewrite),
+            "redirect" => typed(p, Self::Redirect),
+            "route" => typed(p, Self::Route),
+            "set_cache_settings" => typed(p, Self::SetCacheSettings),
+            "set_config" => typed(p, Self::SetConfig),
+            "compress_response" => typed(p, Self::CompressResponse),
+            "serve_error" => typed(p, Self::ServeError),
+            _ => None,
+        };
+        action.unwrap_or(Self::Other {
+            action: raw.action,
+            action_parameters: raw.action_parameters,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BlockParameters {
+    /// Custom response instead of Cloudflare's block page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<CustomResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomResponse {
+    pub status_code: u16,
+    pub content: String,
+    pub content_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryOverride {
+    pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RuleOverride {
+    pub id: RuleId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitivity_level: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ExecuteOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitivity_level: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<CategoryOverride>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<RuleOverride>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ExecuteParameters {
+    pub id: RulesetId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<ExecuteOverrides>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SkipParameters {
+    /// `"current"` skips the remaining rules of this ruleset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ruleset: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<RulesetPhase>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub products: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rulesets: Vec<RulesetId>,
+    /// Rules to skip, keyed by the ruleset that holds them.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rules: HashMap<RulesetId, Vec<RuleId>>,
+}
+
+/// A static value or an expression evaluated per request.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DynamicValue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+}
+
+impl DynamicValue {
+    pub fn value(value: &str) -> Self {
+        Self {
+            value: Some(value.to_string()),
+            expression: None,
+        }
+    }
+
+    pub fn expression(expression: &str) -> Self {
+        Self {
+            value: None,
+            expression: Some(expression.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct UriRewrite {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<DynamicValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<DynamicValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HeaderOperation {
+    Set,
+    Add,
+    Remove,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderRewrite {
+    pub operation: HeaderOperation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RewriteParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<UriRewrite>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, HeaderRewrite>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RedirectFromValue {
+    pub target_url: DynamicValue,
+    /// 301, 302, 307 or 308. Cloudflare uses 301 when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preserve_query_string: Option<bool>,
+}
+
+/// Looks the target up in an account-level bulk redirect list.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RedirectFromList {
+    pub name: String,
+    pub key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RedirectParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_value: Option<RedirectFromValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_list: Option<RedirectFromList>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RouteOrigin {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RouteSni {
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RouteParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_header: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<RouteOrigin>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sni: Option<RouteSni>,
+}
+
+/// `mode` is `respect_origin`, `override_origin` or `bypass_by_default`; `default` is in
+/// seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CacheTtl {
+    pub mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<u32>,
+    /// Kept raw; each entry matches a status code or a range of them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code_ttl: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ServeStale {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_stale_while_updating: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SetCacheSettingsParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge_ttl: Option<CacheTtl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browser_ttl: Option<CacheTtl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serve_stale: Option<ServeStale>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub respect_strong_etags: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin_error_page_passthru: Option<bool>,
+    /// Kept raw; the cache key definition has many optional parts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_key: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_cacheable_ports: Vec<u16>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SetConfigParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatic_https_rewrites: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_apps: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_zaraz: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_obfuscation: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotlink_protection: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opportunistic_encryption: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub polish: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rocket_loader: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_level: Option<SecurityLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssl: Option<SslMode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CompressionAlgorithm {
+    /// `gzip`, `brotli`, `zstd`, `auto` or `none`.
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CompressResponseParameters {
+    pub algorithms: Vec<CompressionAlgorithm>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ServeErrorParameters {
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+}
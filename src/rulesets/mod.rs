@@ -0,0 +1,638 @@
+use hyper::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    AccountId, ApiError, ApiErrorKind, ApiResponse, ApiResult, Cloudflare, PerformOptions, RuleId,
+    RulesetId, Timestamp, ZoneId,
+};
+
+pub mod actions;
+pub use actions::{
+    BlockParameters, CacheTtl, CategoryOverride, CompressResponseParameters, CompressionAlgorithm,
+    CustomResponse, DynamicValue, ExecuteOverrides, ExecuteParameters, HeaderOperation,
+    HeaderRewrite, RedirectFromList, RedirectFromValue, RedirectParameters, RewriteParameters,
+    RouteOrigin, RouteParameters, RouteSni, RuleAction, RuleOverride, ServeErrorParameters,
+    ServeStale, SetCacheSettingsParameters, SetConfigParameters, SkipParameters, UriRewrite,
+};
+
+/// Rulesets live either on a zone or on an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulesetScope {
+    Zone(ZoneId),
+    Account(AccountId),
+}
+
+impl RulesetScope {
+    fn path(&self, rest: &str) -> String {
+        match self {
+            Self::Zone(id) => format!("zones/{}/rulesets{}", id, rest),
+            Self::Account(id) => format!("accounts/{}/rulesets{}", id, rest),
+        }
+    }
+}
+
+impl From<ZoneId> for RulesetScope {
+    fn from(id: ZoneId) -> Self {
+        Self::Zone(id)
+    }
+}
+
+impl From<AccountId> for RulesetScope {
+    fn from(id: AccountId) -> Self {
+        Self::Account(id)
+    }
+}
+
+/// Kinds this crate does not know yet come through as `Other`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RulesetKind {
+    Managed,
+    Custom,
+    /// Account-level entry point.
+    Root,
+    /// Zone-level entry point.
+    Zone,
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// Phases this crate does not know yet come through as `Other`, so listing rulesets keeps
+/// working as Cloudflare adds phases.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RulesetPhase {
+    DdosL4,
+    DdosL7,
+    HttpConfigSettings,
+    HttpCustomErrors,
+    HttpLogCustomFields,
+    HttpRatelimit,
+    HttpRequestCacheSettings,
+    HttpRequestDynamicRedirect,
+    HttpRequestFirewallCustom,
+    HttpRequestFirewallManaged,
+    HttpRequestLateTransform,
+    HttpRequestOrigin,
+    HttpRequestRedirect,
+    HttpRequestSanitize,
+    HttpRequestSbfm,
+    HttpRequestTransform,
+    HttpResponseCompression,
+    HttpResponseFirewallManaged,
+    HttpResponseHeadersTransform,
+    MagicTransit,
+    MagicTransitIdsManaged,
+    MagicTransitManaged,
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl RulesetPhase {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::DdosL4 => "ddos_l4",
+            Self::DdosL7 => "ddos_l7",
+            Self::HttpConfigSettings => "http_config_settings",
+            Self::HttpCustomErrors => "http_custom_errors",
+            Self::HttpLogCustomFields => "http_log_custom_fields",
+            Self::HttpRatelimit => "http_ratelimit",
+            Self::HttpRequestCacheSettings => "http_request_cache_settings",
+            Self::HttpRequestDynamicRedirect => "http_request_dynamic_redirect",
+            Self::HttpRequestFirewallCustom => "http_request_firewall_custom",
+            Self::HttpRequestFirewallManaged => "http_request_firewall_managed",
+            Self::HttpRequestLateTransform => "http_request_late_transform",
+            Self::HttpRequestOrigin => "http_request_origin",
+            Self::HttpRequestRedirect => "http_request_redirect",
+            Self::HttpRequestSanitize => "http_request_sanitize",
+            Self::HttpRequestSbfm => "http_request_sbfm",
+            Self::HttpRequestTransform => "http_request_transform",
+            Self::HttpResponseCompression => "http_response_compression",
+            Self::HttpResponseFirewallManaged => "http_response_firewall_managed",
+            Self::HttpResponseHeadersTransform => "http_response_headers_transform",
+            Self::MagicTransit => "magic_transit",
+            Self::MagicTransitIdsManaged => "magic_transit_ids_managed",
+            Self::MagicTransitManaged => "magic_transit_managed",
+            Self::Other(phase) => phase,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RuleLogging {
+    pub enabled: bool,
+}
+
+/// Rate limiting parameters, for rules in the `http_ratelimit` phase.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RuleRateLimit {
+    /// Request properties counted separately, e.g. `ip.src` and `cf.colo.id`.
+    pub characteristics: Vec<String>,
+    /// Seconds.
+    pub period: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_per_period: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_per_period: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_response_header_name: Option<String>,
+    /// Seconds the action stays in effect once the limit is hit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mitigation_timeout: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counting_expression: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_to_origin: Option<bool>,
+}
+
+/// A rule as returned by the API, and as sent when creating or updating one. The server-set
+/// fields (`id`, `version`, `last_updated`) may be left empty on new rules.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Rule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<RuleId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(flatten)]
+    pub action: RuleAction,
+    /// Wirefilter expression, e.g. `http.request.uri.path eq "/admin"`.
+    pub expression: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Caller-chosen identifier that stays stable across ruleset versions.
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging: Option<RuleLogging>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ratelimit: Option<RuleRateLimit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_updated: Option<Timestamp>,
+}
+
+impl Rule {
+    pub fn new(expression: &str, action: RuleAction) -> Self {
+        Self {
+            id: None,
+            version: None,
+            action,
+            expression: expression.to_string(),
+            description: None,
+            enabled: None,
+            reference: None,
+            logging: None,
+            ratelimit: None,
+            categories: vec![],
+            last_updated: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    pub fn with_ref(mut self, reference: &str) -> Self {
+        self.reference = Some(reference.to_string());
+        self
+    }
+
+    pub fn with_ratelimit(mut self, ratelimit: RuleRateLimit) -> Self {
+        self.ratelimit = Some(ratelimit);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Ruleset {
+    pub id: RulesetId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<RulesetKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<RulesetPhase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_updated: Option<Timestamp>,
+    /// Empty in listings, which leave out the rules.
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl Ruleset {
+    pub fn rule(&self, id: &RuleId) -> Option<&Rule> {
+        self.rules.iter().find(|r| r.id.as_ref() == Some(id))
+    }
+
+    pub fn rule_by_ref(&self, reference: &str) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .find(|r| r.reference.as_deref() == Some(reference))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateRulesetRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub kind: RulesetKind,
+    pub phase: RulesetPhase,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// Replaces a ruleset's rules. Rules sent with their `id` keep it; the rest are created.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UpdateRulesetRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// Where a rule goes within its ruleset. New rules go last by default.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RulePosition {
+    Before(RuleId),
+    After(RuleId),
+    /// 1-based.
+    Index(usize),
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct RuleRequest {
+    #[serde(flatten)]
+    rule: Rule,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position: Option<RulePosition>,
+}
+
+pub struct CloudflareRulesets {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareRulesets {
+    async fn call<R, T>(
+        &self,
+        method: Method,
+        path: String,
+        body: Option<R>,
+    ) -> ApiResult<ApiResponse<T>>
+    where
+        R: Serialize + Clone,
+        T: DeserializeOwned,
+    {
+        self.c
+            .clone()
+            .perform_json(
+                method,
+                PerformOptions {
+                    path,
+                    params: None,
+                    body,
+                },
+            )
+            .await
+    }
+
+    /// DELETE endpoints here answer `204 No Content`.
+    async fn delete(&self, path: String) -> ApiResult<()> {
+        let (status, body) = self
+            .c
+            .clone()
+            .perform_raw(Method::DELETE, &path, None, None, None)
+            .await?;
+        if status.is_success() {
+            return Ok(());
+        }
+        match serde_json::from_str::<ApiResponse<serde_json::Value>>(&body) {
+            Ok(res) => res.into_result().map(|_| ()),
+            Err(_) => Err(ApiError::with_kind(
+                ApiErrorKind::from_status(status),
+                format!("delete failed with {}: {}", status, body),
+            )),
+        }
+    }
+
+    /// Rulesets visible in the scope, without their rules. Includes the managed rulesets the
+    /// zone or account can deploy.
+    pub async fn list(&self, scope: &RulesetScope) -> ApiResult<ApiResponse<Vec<Ruleset>>> {
+        self.call::<(), _>(Method::GET, scope.path(""), None).await
+    }
+
+    pub async fn get(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!("/{}", ruleset_id));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn create(
+        &self,
+        scope: &RulesetScope,
+        req: CreateRulesetRequest,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        self.call(Method::POST, scope.path(""), Some(req)).await
+    }
+
+    pub async fn update(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+        req: UpdateRulesetRequest,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!("/{}", ruleset_id));
+        self.call(Method::PUT, path, Some(req)).await
+    }
+
+    /// Deletes every version of the ruleset.
+    pub async fn delete_ruleset(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+    ) -> ApiResult<()> {
+        self.delete(scope.path(&format!("/{}", ruleset_id))).await
+    }
+
+    /// Adds a rule and returns the updated ruleset.
+    pub async fn create_rule(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+        rule: Rule,
+        position: Option<RulePosition>,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!("/{}/rules", ruleset_id));
+        self.call(Method::POST, path, Some(RuleRequest { rule, position }))
+            .await
+    }
+
+    /// Replaces one rule, optionally moving it, and returns the updated ruleset.
+    pub async fn update_rule(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+        rule_id: &RuleId,
+        rule: Rule,
+        position: Option<RulePosition>,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!("/{}/rules/{}", ruleset_id, rule_id));
+        self.call(Method::PATCH, path, Some(RuleRequest { rule, position }))
+            .await
+    }
+
+    pub async fn delete_rule(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+        rule_id: &RuleId,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!("/{}/rules/{}", ruleset_id, rule_id));
+        self.call::<(), _>(Method::DELETE, path, None).await
+    }
+
+    /// The scope's entry point ruleset for a phase. Answers with a not-found error until the
+    /// first rule is deployed to the phase.
+    pub async fn entrypoint(
+        &self,
+        scope: &RulesetScope,
+        phase: RulesetPhase,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!("/phases/{}/entrypoint", phase.as_str()));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    /// Replaces the rules of a phase's entry point ruleset, creating it if needed.
+    pub async fn update_entrypoint(
+        &self,
+        scope: &RulesetScope,
+        phase: RulesetPhase,
+        req: UpdateRulesetRequest,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!("/phases/{}/entrypoint", phase.as_str()));
+        self.call(Method::PUT, path, Some(req)).await
+    }
+
+    pub async fn entrypoint_versions(
+        &self,
+        scope: &RulesetScope,
+        phase: RulesetPhase,
+    ) -> ApiResult<ApiResponse<Vec<Ruleset>>> {
+        let path = scope.path(&format!("/phases/{}/entrypoint/versions", phase.as_str()));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn entrypoint_version(
+        &self,
+        scope: &RulesetScope,
+        phase: RulesetPhase,
+        version: &str,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!(
+            "/phases/{}/entrypoint/versions/{}",
+            phase.as_str(),
+            version
+        ));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    /// Earlier versions of a ruleset, without their rules.
+    pub async fn versions(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+    ) -> ApiResult<ApiResponse<Vec<Ruleset>>> {
+        let path = scope.path(&format!("/{}/versions", ruleset_id));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn version(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+        version: &str,
+    ) -> ApiResult<ApiResponse<Ruleset>> {
+        let path = scope.path(&format!("/{}/versions/{}", ruleset_id, version));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    /// The latest version cannot be deleted.
+    pub async fn delete_version(
+        &self,
+        scope: &RulesetScope,
+        ruleset_id: &RulesetId,
+        version: &str,
+    ) -> ApiResult<()> {
+        self.delete(scope.path(&format!("/{}/versions/{}", ruleset_id, version)))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULESET: &str = r#"{
+        "id": "2f2feab2026849078ba485f918791bdc",
+        "name": "default",
+        "kind": "zone",
+        "phase": "http_request_firewall_custom",
+        "version": "3",
+        "last_updated": "2024-05-01T12:00:00Z",
+        "rules": [
+            {
+                "id": "3a03d665bac047339bb530ecb439a90d",
+                "version": "1",
+                "action": "block",
+                "expression": "ip.src eq 192.0.2.1",
+                "enabled": true,
+                "ref": "block-bad-ip"
+            },
+            {
+                "id": "4814384a9e5d4991b9815dcfc25d2f1f",
+                "action": "redirect",
+                "action_parameters": {
+                    "from_value": {
+                        "target_url": {"value": "https://example.com/new"},
+                        "status_code": 301,
+                        "preserve_query_string": true
+                    }
+                },
+                "expression": "http.request.uri.path eq \"/old\""
+            },
+            {
+                "id": "52e0a7c3b8a54e1d9d1f5a8c6b7e4f20",
+                "action": "set_cache_settings",
+                "action_parameters": {"cache": true, "read_timeout": 900},
+                "expression": "true"
+            },
+            {
+                "id": "6d0a1f2b3c4d4e5f8a9b0c1d2e3f4a5b",
+                "action": "execute",
+                "action_parameters": {
+                    "id": "efb7b8c949ac4650a09736fc376e9aee",
+                    "overrides": {
+                        "rules": [
+                            {
+                                "id": "5de7edfa648c4d6891dc3e7f84534ffa",
+                                "action": "log",
+                                "future_option": true
+                            }
+                        ]
+                    }
+                },
+                "expression": "true"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn decodes_typed_actions() {
+        let ruleset: Ruleset = serde_json::from_str(RULESET).unwrap();
+        assert_eq!(ruleset.phase, Some(RulesetPhase::HttpRequestFirewallCustom));
+
+        let block = ruleset.rule_by_ref("block-bad-ip").unwrap();
+        assert_eq!(block.action, RuleAction::Block(BlockParameters::default()));
+
+        let redirect = &ruleset.rules[1].action;
+        let RuleAction::Redirect(params) = redirect else {
+            panic!("expected a redirect, got {:?}", redirect);
+        };
+        let from_value = params.from_value.as_ref().unwrap();
+        assert_eq!(
+            from_value.target_url,
+            DynamicValue::value("https://example.com/new")
+        );
+        assert_eq!(from_value.status_code, Some(301));
+
+        // A parameter the typed form lacks keeps the action raw, so updates send it back.
+        let cache = &ruleset.rules[2].action;
+        assert!(
+            matches!(cache, RuleAction::Other { action, .. } if action == "set_cache_settings")
+        );
+        // The same holds for unknown fields nested inside the parameters.
+        let execute = &ruleset.rules[3].action;
+        assert!(matches!(execute, RuleAction::Other { action, .. } if action == "execute"));
+
+        let json = serde_json::to_value(&ruleset).unwrap();
+        let original: serde_json::Value = serde_json::from_str(RULESET).unwrap();
+        assert_eq!(json["rules"], original["rules"]);
+    }
+
+    #[test]
+    fn keeps_unknown_phases_and_kinds() {
+        let rulesets: Vec<Ruleset> = serde_json::from_value(serde_json::json!([
+            {
+                "id": "2f2feab2026849078ba485f918791bdc",
+                "name": "future",
+                "kind": "vendor",
+                "phase": "http_request_future"
+            },
+            {
+                "id": "6e4d9a7d2a5a4b7f9f7b1c2d3e4f5a6b",
+                "name": "custom",
+                "kind": "custom",
+                "phase": "http_request_firewall_custom"
+            }
+        ]))
+        .unwrap();
+        let future = &rulesets[0];
+        assert_eq!(future.kind, Some(RulesetKind::Other("vendor".to_string())));
+        let phase = future.phase.as_ref().unwrap();
+        assert_eq!(phase.as_str(), "http_request_future");
+        assert_eq!(rulesets[1].kind, Some(RulesetKind::Custom));
+        let json = serde_json::to_value(future).unwrap();
+        assert_eq!(json["phase"], "http_request_future");
+        assert_eq!(json["kind"], "vendor");
+    }
+
+    #[test]
+    fn encodes_rule_requests() {
+        let rule = Rule::new(
+            "http.request.uri.path eq \"/admin\"",
+            RuleAction::ManagedChallenge,
+        )
+        .with_ref("challenge-admin");
+        let after: RuleId = "3a03d665bac047339bb530ecb439a90d".parse().unwrap();
+        let body = serde_json::to_value(RuleRequest {
+            rule,
+            position: Some(RulePosition::After(after)),
+        })
+        .unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "action": "managed_challenge",
+                "expression": "http.request.uri.path eq \"/admin\"",
+                "ref": "challenge-admin",
+                "position": {"after": "3a03d665bac047339bb530ecb439a90d"}
+            })
+        );
+        assert_eq!(
+            RulesetScope::from(
+                "023e105f4ecef8ad9ca31a8372d0c353"
+                    .parse::<AccountId>()
+                    .unwrap()
+            )
+            .path("/phases/http_ratelimit/entrypoint"),
+            "accounts/023e105f4ecef8ad9ca31a8372d0c353/rulesets/phases/http_ratelimit/entrypoint"
+        );
+    }
+}
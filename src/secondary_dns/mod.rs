@@ -0,0 +1,455 @@
+use std::net::IpAddr;
+
+use hyper::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    AccountId, AclId, ApiResponse, ApiResult, Cloudflare, PeerId, PerformOptions, Timestamp,
+    TsigId, ZoneId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IdResult<T> {
+    pub id: T,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Tsig {
+    pub id: TsigId,
+    pub name: String,
+    /// Algorithm name as used on the wire, e.g. `hmac-sha512.`.
+    pub algo: String,
+    pub secret: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TsigRequest {
+    pub name: String,
+    pub algo: String,
+    pub secret: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Peer {
+    pub id: PeerId,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<IpAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ixfr_enable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tsig_id: Option<TsigId>,
+}
+
+/// Body of [`CloudflareSecondaryDns::update_peer`]. Peers are created with a name only, see
+/// [`CloudflareSecondaryDns::create_peer`].
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PeerRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<IpAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ixfr_enable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tsig_id: Option<TsigId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Acl {
+    pub id: AclId,
+    pub name: String,
+    /// CIDR allowed to transfer zones from Cloudflare, e.g. `192.0.2.53/28`.
+    pub ip_range: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AclRequest {
+    pub name: String,
+    pub ip_range: String,
+}
+
+/// Cloudflare as secondary, pulling the zone from the `peers`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IncomingTransfer {
+    pub id: ZoneId,
+    pub name: String,
+    #[serde(default)]
+    pub peers: Vec<PeerId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_refresh_seconds: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soa_serial: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_time: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_time: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_time: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IncomingTransferRequest {
+    pub name: String,
+    pub peers: Vec<PeerId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_refresh_seconds: Option<u32>,
+}
+
+/// Cloudflare as primary, notifying and serving transfers to the `peers`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OutgoingTransfer {
+    pub id: ZoneId,
+    pub name: String,
+    #[serde(default)]
+    pub peers: Vec<PeerId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soa_serial: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_time: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_time: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_transferred_time: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OutgoingTransferRequest {
+    pub name: String,
+    pub peers: Vec<PeerId>,
+}
+
+pub struct CloudflareSecondaryDns {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareSecondaryDns {
+    async fn call<R, T>(
+        &self,
+        method: Method,
+        path: String,
+        body: Option<R>,
+    ) -> ApiResult<ApiResponse<T>>
+    where
+        R: Serialize + Clone,
+        T: DeserializeOwned,
+    {
+        self.c
+            .clone()
+            .perform_json(
+                method,
+                PerformOptions {
+                    path,
+                    params: None,
+                    body,
+                },
+            )
+            .await
+    }
+
+    fn account_path(account_id: &AccountId, rest: &str) -> String {
+        format!("accounts/{}/secondary_dns/{}", account_id, rest)
+    }
+
+    fn zone_path(zone_id: &ZoneId, rest: &str) -> String {
+        format!("zones/{}/secondary_dns/{}", zone_id, rest)
+    }
+
+    pub async fn list_tsigs(&self, account_id: &AccountId) -> ApiResult<ApiResponse<Vec<Tsig>>> {
+        let path = Self::account_path(account_id, "tsigs");
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn get_tsig(
+        &self,
+        account_id: &AccountId,
+        tsig_id: &TsigId,
+    ) -> ApiResult<ApiResponse<Tsig>> {
+        let path = Self::account_path(account_id, &format!("tsigs/{}", tsig_id));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn create_tsig(
+        &self,
+        account_id: &AccountId,
+        req: TsigRequest,
+    ) -> ApiResult<ApiResponse<Tsig>> {
+        let path = Self::account_path(account_id, "tsigs");
+        self.call(Method::POST, path, Some(req)).await
+    }
+
+    pub async fn update_tsig(
+        &self,
+        account_id: &AccountId,
+        tsig_id: &TsigId,
+        req: TsigRequest,
+    ) -> ApiResult<ApiResponse<Tsig>> {
+        let path = Self::account_path(account_id, &format!("tsigs/{}", tsig_id));
+        self.call(Method::PUT, path, Some(req)).await
+    }
+
+    pub async fn delete_tsig(
+        &self,
+        account_id: &AccountId,
+        tsig_id: &TsigId,
+    ) -> ApiResult<ApiResponse<IdResult<TsigId>>> {
+        let path = Self::account_path(account_id, &format!("tsigs/{}", tsig_id));
+        self.call::<(), _>(Method::DELETE, path, None).await
+    }
+
+    pub async fn list_peers(&self, account_id: &AccountId) -> ApiResult<ApiResponse<Vec<Peer>>> {
+        let path = Self::account_path(account_id, "peers");
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn get_peer(
+        &self,
+        account_id: &AccountId,
+        peer_id: &PeerId,
+    ) -> ApiResult<ApiResponse<Peer>> {
+        let path = Self::account_path(account_id, &format!("peers/{}", peer_id));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    /// Creates a peer with only a name, since the API ignores every other field here. Set the
+    /// address, port and TSIG key afterwards with [`CloudflareSecondaryDns::update_peer`]
+    /// using the returned id; if that fails the peer still exists and can be retried or
+    /// deleted by id.
+    pub async fn create_peer(
+        &self,
+        account_id: &AccountId,
+        name: &str,
+    ) -> ApiResult<ApiResponse<Peer>> {
+        let path = Self::account_path(account_id, "peers");
+        let req = PeerRequest {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        self.call(Method::POST, path, Some(req)).await
+    }
+
+    pub async fn update_peer(
+        &self,
+        account_id: &AccountId,
+        peer_id: &PeerId,
+        req: PeerRequest,
+    ) -> ApiResult<ApiResponse<Peer>> {
+        let path = Self::account_path(account_id, &format!("peers/{}", peer_id));
+        self.call(Method::PUT, path, Some(req)).await
+    }
+
+    pub async fn delete_peer(
+        &self,
+        account_id: &AccountId,
+        peer_id: &PeerId,
+    ) -> ApiResult<ApiResponse<IdResult<PeerId>>> {
+        let path = Self::account_path(account_id, &format!("peers/{}", peer_id));
+        self.call::<(), _>(Method::DELETE, path, None).await
+    }
+
+    pub async fn list_acls(&self, account_id: &AccountId) -> ApiResult<ApiResponse<Vec<Acl>>> {
+        let path = Self::account_path(account_id, "acls");
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn get_acl(
+        &self,
+        account_id: &AccountId,
+        acl_id: &AclId,
+    ) -> ApiResult<ApiResponse<Acl>> {
+        let path = Self::account_path(account_id, &format!("acls/{}", acl_id));
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn create_acl(
+        &self,
+        account_id: &AccountId,
+        req: AclRequest,
+    ) -> ApiResult<ApiResponse<Acl>> {
+        let path = Self::account_path(account_id, "acls");
+        self.call(Method::POST, path, Some(req)).await
+    }
+
+    pub async fn update_acl(
+        &self,
+        account_id: &AccountId,
+        acl_id: &AclId,
+        req: AclRequest,
+    ) -> ApiResult<ApiResponse<Acl>> {
+        let path = Self::account_path(account_id, &format!("acls/{}", acl_id));
+        self.call(Method::PUT, path, Some(req)).await
+    }
+
+    pub async fn delete_acl(
+        &self,
+        account_id: &AccountId,
+        acl_id: &AclId,
+    ) -> ApiResult<ApiResponse<IdResult<AclId>>> {
+        let path = Self::account_path(account_id, &format!("acls/{}", acl_id));
+        self.call::<(), _>(Method::DELETE, path, None).await
+    }
+
+    pub async fn get_incoming(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<IncomingTransfer>> {
+        let path = Self::zone_path(zone_id, "incoming");
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn create_incoming(
+        &self,
+        zone_id: &ZoneId,
+        req: IncomingTransferRequest,
+    ) -> ApiResult<ApiResponse<IncomingTransfer>> {
+        let path = Self::zone_path(zone_id, "incoming");
+        self.call(Method::POST, path, Some(req)).await
+    }
+
+    pub async fn update_incoming(
+        &self,
+        zone_id: &ZoneId,
+        req: IncomingTransferRequest,
+    ) -> ApiResult<ApiResponse<IncomingTransfer>> {
+        let path = Self::zone_path(zone_id, "incoming");
+        self.call(Method::PUT, path, Some(req)).await
+    }
+
+    pub async fn delete_incoming(
+        &self,
+        zone_id: &ZoneId,
+    ) -> ApiResult<ApiResponse<IdResult<ZoneId>>> {
+        let path = Self::zone_path(zone_id, "incoming");
+        self.call::<(), _>(Method::DELETE, path, None).await
+    }
+
+    /// Pulls the zone from the primary now instead of waiting for the next refresh.
+    pub async fn force_axfr(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<String>> {
+        let path = Self::zone_path(zone_id, "force_axfr");
+        self.call::<(), _>(Method::POST, path, None).await
+    }
+
+    pub async fn get_outgoing(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<OutgoingTransfer>> {
+        let path = Self::zone_path(zone_id, "outgoing");
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    pub async fn create_outgoing(
+        &self,
+        zone_id: &ZoneId,
+        req: OutgoingTransferRequest,
+    ) -> ApiResult<ApiResponse<OutgoingTransfer>> {
+        let path = Self::zone_path(zone_id, "outgoing");
+        self.call(Method::POST, path, Some(req)).await
+    }
+
+    pub async fn update_outgoing(
+        &self,
+        zone_id: &ZoneId,
+        req: OutgoingTransferRequest,
+    ) -> ApiResult<ApiResponse<OutgoingTransfer>> {
+        let path = Self::zone_path(zone_id, "outgoing");
+        self.call(Method::PUT, path, Some(req)).await
+    }
+
+    pub async fn delete_outgoing(
+        &self,
+        zone_id: &ZoneId,
+    ) -> ApiResult<ApiResponse<IdResult<ZoneId>>> {
+        let path = Self::zone_path(zone_id, "outgoing");
+        self.call::<(), _>(Method::DELETE, path, None).await
+    }
+
+    /// Starts serving outgoing transfers. The result is the new status, e.g. `Enabled`.
+    pub async fn enable_outgoing(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<String>> {
+        let path = Self::zone_path(zone_id, "outgoing/enable");
+        self.call::<(), _>(Method::POST, path, None).await
+    }
+
+    pub async fn disable_outgoing(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<String>> {
+        let path = Self::zone_path(zone_id, "outgoing/disable");
+        self.call::<(), _>(Method::POST, path, None).await
+    }
+
+    pub async fn outgoing_status(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<String>> {
+        let path = Self::zone_path(zone_id, "outgoing/status");
+        self.call::<(), _>(Method::GET, path, None).await
+    }
+
+    /// Sends NOTIFY to the secondaries so they pull the zone.
+    pub async fn force_notify(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<String>> {
+        let path = Self::zone_path(zone_id, "outgoing/force_notify");
+        self.call::<(), _>(Method::POST, path, None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "01a7362d577a6c3019a474fd6f485823";
+    const ZONE: &str = "023e105f4ecef8ad9ca31a8372d0c353";
+
+    #[test]
+    fn builds_account_and_zone_paths() {
+        let account = AccountId::try_from(ACCOUNT).unwrap();
+        let zone = ZoneId::try_from(ZONE).unwrap();
+        assert_eq!(
+            CloudflareSecondaryDns::account_path(&account, "peers/abc"),
+            format!("accounts/{}/secondary_dns/peers/abc", ACCOUNT)
+        );
+        assert_eq!(
+            CloudflareSecondaryDns::zone_path(&zone, "outgoing/force_notify"),
+            format!("zones/{}/secondary_dns/outgoing/force_notify", ZONE)
+        );
+    }
+
+    #[test]
+    fn peer_requests_skip_unset_fields() {
+        let req = PeerRequest {
+            name: "primary".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&req).unwrap(),
+            serde_json::json!({"name": "primary"})
+        );
+
+        let peer: Peer = serde_json::from_value(serde_json::json!({
+            "id": "23ff594956f20c2a721606e94745a8aa",
+            "name": "my-peer-1",
+            "ip": "192.0.2.53",
+            "port": 53,
+            "ixfr_enable": false,
+            "tsig_id": "69cd1e104af3e6ed3cb344f263fd0d5a"
+        }))
+        .unwrap();
+        assert_eq!(peer.ip, Some("192.0.2.53".parse().unwrap()));
+        assert_eq!(peer.port, Some(53));
+        assert!(peer.tsig_id.is_some());
+    }
+
+    #[test]
+    fn decodes_transfers() {
+        let incoming: IncomingTransfer = serde_json::from_value(serde_json::json!({
+            "id": ZONE,
+            "name": "www.example.com.",
+            "peers": ["23ff594956f20c2a721606e94745a8aa"],
+            "auto_refresh_seconds": 86400,
+            "soa_serial": 2019102400,
+            "checked_time": "2019-10-24T17:09:42.883908+01:00"
+        }))
+        .unwrap();
+        assert_eq!(incoming.peers.len(), 1);
+        assert_eq!(incoming.soa_serial, Some(2019102400));
+        assert_eq!(
+            incoming.checked_time.unwrap().as_str(),
+            "2019-10-24T17:09:42.883908+01:00"
+        );
+
+        let outgoing: OutgoingTransfer =
+            serde_json::from_value(serde_json::json!({"id": ZONE, "name": "example.com."}))
+                .unwrap();
+        assert!(outgoing.peers.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use hyper::{body::Bytes, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{relative_uri, ApiError, ApiErrorKind, ApiResult, Cloudflare, QueryParams};
+
+const REDACTED: &str = "REDACTED";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Sends requests for real and remembers every exchange.
+    Record,
+    /// Answers from the recorded exchanges without touching the network.
+    Replay,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path below `/client/v4/`, with the query string sorted.
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// VCR-style fixture for [`Cloudflare::with_cassette`]. In record mode the client talks to
+/// the real API and every exchange is kept, with the client's token, API key and email
+/// replaced by `REDACTED`, until [`Cassette::save`] writes them out as JSON. In replay mode
+/// each request is answered by the first unused exchange with the same method and URI;
+/// bodies are kept for reference only, so they may be edited freely.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<(Vec<Interaction>, Vec<bool>)>,
+}
+
+impl Cassette {
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            mode: CassetteMode::Record,
+            state: Mutex::new((vec![], vec![])),
+        }
+    }
+
+    pub fn replay(path: impl AsRef<Path>) -> ApiResult<Self> {
+        let path = path.as_ref();
+        let invalid = |e: String| {
+            ApiError::with_kind(ApiErrorKind::Invalid, format!("{}: {}", path.display(), e))
+        };
+        let s = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        let interactions: Vec<Interaction> =
+            serde_json::from_str(&s).map_err(|e| invalid(e.to_string()))?;
+        let used = vec![false; interactions.len()];
+        Ok(Self {
+            path: path.to_path_buf(),
+            mode: CassetteMode::Replay,
+            state: Mutex::new((interactions, used)),
+        })
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.lock().0.clone()
+    }
+
+    /// Exchanges not yet replayed, useful to assert a test made every expected call.
+    pub fn unused(&self) -> Vec<Interaction> {
+        let state = self.lock();
+        state
+            .0
+            .iter()
+            .zip(&state.1)
+            .filter(|(_, used)| !**used)
+            .map(|(i, _)| i.clone())
+            .collect()
+    }
+
+    pub fn save(&self) -> ApiResult<()> {
+        let s = serde_json::to_string_pretty(&self.lock().0)
+            .map_err(|e| ApiError::with_kind(ApiErrorKind::Invalid, e))?;
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| self.io_error(e))?;
+        }
+        std::fs::write(&self.path, s + "\n").map_err(|e| self.io_error(e))
+    }
+
+    fn io_error(&self, e: std::io::Error) -> ApiError {
+        ApiError::with_kind(
+            ApiErrorKind::Invalid,
+            format!("{}: {}", self.path.display(), e),
+        )
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (Vec<Interaction>, Vec<bool>)> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) async fn perform(
+        &self,
+        c: Cloudflare,
+        method: Method,
+        path: &str,
+        params: Option<QueryParams>,
+        content_type: Option<&str>,
+        body: Option<Bytes>,
+    ) -> ApiResult<(StatusCode, String)> {
+        let uri = relative_uri(path, params.clone());
+        match self.mode {
+            CassetteMode::Replay => self.replay_one(&method, &uri),
+            CassetteMode::Record => {
+                let secrets: Vec<String> = [&c.auth_key, &c.api_key, &c.email]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .cloned()
+                    .collect();
+                let scrub = |s: &str| {
+                    secrets
+                        .iter()
+                        .fold(s.to_string(), |s, secret| s.replace(secret, REDACTED))
+                };
+                let request_body = body
+                    .as_ref()
+                    .map(|b| String::from_utf8_lossy(b).into_owned());
+                let (status, res) = c
+                    .send_raw(method.clone(), path, params, content_type, body)
+                    .await?;
+
+                let (json, text) = split_body(request_body.as_deref().map(scrub));
+                let request = RecordedRequest {
+                    method: method.to_string(),
+                    uri: scrub(&uri),
+                    content_type: content_type.map(str::to_string),
+                    json,
+                    text,
+                };
+                let (json, text) = split_body(Some(scrub(&res)));
+                let response = RecordedResponse {
+                    status: status.as_u16(),
+                    json,
+                    text,
+                };
+                let mut state = self.lock();
+                state.0.push(Interaction { request, response });
+                state.1.push(true);
+                Ok((status, res))
+            }
+        }
+    }
+
+    fn replay_one(&self, method: &Method, uri: &str) -> ApiResult<(StatusCode, String)> {
+        let mut state = self.lock();
+        let (interactions, used) = &mut *state;
+        let found = interactions.iter().enumerate().position(|(i, x)| {
+            !used[i] && x.request.method == method.as_str() && x.request.uri == uri
+        });
+        let Some(i) = found else {
+            return Err(ApiError::with_kind(
+                ApiErrorKind::Transport,
+                format!(
+                    "no recorded response for {} {} in {}",
+                    method,
+                    uri,
+                    self.path.display()
+                ),
+            ));
+        };
+        used[i] = true;
+        let response = &interactions[i].response;
+        let status = StatusCode::from_u16(response.status)
+            .map_err(|e| ApiError::with_kind(ApiErrorKind::Invalid, e))?;
+        let body = match (&response.json, &response.text) {
+            (Some(json), _) => json.to_string(),
+            (None, Some(text)) => text.clone(),
+            (None, None) => String::new(),
+        };
+        Ok((status, body))
+    }
+}
+
+/// Keeps JSON bodies as JSON so fixtures stay readable, and anything else as text.
+fn split_body(body: Option<String>) -> (Option<Value>, Option<String>) {
+    match body {
+        None => (None, None),
+        Some(b) if b.is_empty() => (None, None),
+        Some(b) => match serde_json::from_str::<Value>(&b) {
+            Ok(v) if v.is_object() || v.is_array() => (Some(v), None),
+            _ => (None, Some(b)),
+        },
+    }
+}
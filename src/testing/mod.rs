@@ -0,0 +1,886 @@
+//! In-process fake of the Cloudflare zones and DNS records API, for integration tests that
+//! should run offline.
+//!
+//! ```no_run
+//! # async fn example() {
+//! use cloudflare_rs::{testing::FakeCloudflare, ARecordRequest};
+//!
+//! let fake = FakeCloudflare::start().await;
+//! let zone_id = fake.add_zone("example.com");
+//! let c = fake.client();
+//! c.dns()
+//!     .create_record(
+//!         &zone_id,
+//!         ARecordRequest {
+//!             name: Some("www".to_string()),
+//!             content: Some("192.0.2.1".parse().unwrap()),
+//!             ..Default::default()
+//!         },
+//!     )
+//!     .await
+//!     .unwrap();
+//! assert_eq!(fake.records(&zone_id).len(), 1);
+//! # }
+//! ```
+//!
+//! [`Cassette`] records real traffic to a JSON fixture and replays it later.
+
+mod cassette;
+pub use cassette::*;
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{Bytes, Incoming},
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use serde_json::{json, Map, Value};
+use tokio::{net::TcpListener, task::JoinHandle};
+use url::Url;
+
+use crate::{to_zone_file, Cloudflare, RecordMessage, RecordRequest, Zone, ZoneId};
+
+/// Token accepted by a fresh [`FakeCloudflare`] and set on [`FakeCloudflare::client`].
+pub const TEST_TOKEN: &str = "test-token";
+
+const DEFAULT_RECORDS_PER_PAGE: usize = 100;
+const MAX_RECORDS_PER_PAGE: usize = 5000;
+const DEFAULT_ZONES_PER_PAGE: usize = 20;
+const MAX_ZONES_PER_PAGE: usize = 50;
+const NAME_SERVERS: [&str; 2] = ["ada.ns.cloudflare.com", "bob.ns.cloudflare.com"];
+const RECORD_TYPES: [&str; 17] = [
+    "A", "AAAA", "CAA", "CERT", "CNAME", "DNSKEY", "DS", "HTTPS", "LOC", "MX", "NAPTR", "NS",
+    "PTR", "SMIMEA", "SRV", "SSHFP", "TXT",
+];
+
+/// Serves `/client/v4/` on a random local port until dropped. Covers token verification,
+/// zone CRUD, and DNS record CRUD, listing, batch and export, with Cloudflare's envelopes,
+/// pagination and error codes.
+pub struct FakeCloudflare {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct State {
+    token: String,
+    next_id: u64,
+    zones: Vec<Value>,
+    records: Vec<Value>,
+    requests: Vec<String>,
+    failures: Vec<Reply>,
+}
+
+struct Reply {
+    status: StatusCode,
+    body: String,
+    content_type: &'static str,
+}
+
+impl FakeCloudflare {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind fake Cloudflare server");
+        let addr = listener.local_addr().expect("fake server has no address");
+        let state = Arc::new(Mutex::new(State {
+            token: TEST_TOKEN.to_string(),
+            ..Default::default()
+        }));
+
+        let server_state = state.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    continue;
+                };
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    let svc = service_fn(move |req| handle(state.clone(), req));
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), svc)
+                        .await;
+                });
+            }
+        });
+
+        Self { addr, state, task }
+    }
+
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}", self.addr)).expect("invalid fake server url")
+    }
+
+    /// A client pointed at this server, authenticated with the accepted token.
+    pub fn client(&self) -> Cloudflare {
+        let token = self.lock().token.clone();
+        Cloudflare::default()
+            .with_base_url(self.url())
+            .with_token(&token)
+    }
+
+    /// Replaces the only token the server accepts.
+    pub fn set_token(&self, token: &str) {
+        self.lock().token = token.to_string();
+    }
+
+    /// Adds an active zone and returns its id.
+    pub fn add_zone(&self, name: &str) -> ZoneId {
+        let mut state = self.lock();
+        let zone = state.new_zone(name, "active", json!({}));
+        let id = zone["id"].as_str().unwrap_or_default().to_string();
+        state.zones.push(zone);
+        ZoneId::try_from(id).expect("fake zone id")
+    }
+
+    /// Adds a record to the zone as if created through the API.
+    ///
+    /// # Panics
+    ///
+    /// If the zone does not exist or the API would reject the record.
+    pub fn add_record(&self, zone_id: &ZoneId, record: impl Into<RecordRequest>) -> RecordMessage {
+        let body = serde_json::to_value(record.into()).expect("record serializes");
+        let mut state = self.lock();
+        let zone = state
+            .zone(zone_id.as_str())
+            .unwrap_or_else(|| panic!("no fake zone {}", zone_id));
+        let record = state
+            .new_record(&zone, body)
+            .unwrap_or_else(|r| panic!("record rejected: {}", r.body));
+        state.records.push(record.clone());
+        serde_json::from_value(record).expect("fake record")
+    }
+
+    pub fn zones(&self) -> Vec<Zone> {
+        self.lock()
+            .zones
+            .iter()
+            .filter_map(|z| serde_json::from_value(z.clone()).ok())
+            .collect()
+    }
+
+    pub fn records(&self, zone_id: &ZoneId) -> Vec<RecordMessage> {
+        self.lock()
+            .records
+            .iter()
+            .filter(|r| r["zone_id"] == zone_id.as_str())
+            .filter_map(|r| serde_json::from_value(r.clone()).ok())
+            .collect()
+    }
+
+    /// Requests received so far, as `METHOD /path?query` relative to `/client/v4`.
+    pub fn requests(&self) -> Vec<String> {
+        self.lock().requests.clone()
+    }
+
+    /// Fails the next request with the given status and Cloudflare error code. Queued failures
+    /// are used in order.
+    pub fn fail_next(&self, status: u16, code: usize, message: &str) {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        self.lock().failures.push(error(status, code, message));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for FakeCloudflare {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    req: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let (parts, body) = req.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(|b| b.to_bytes())
+        .unwrap_or_default();
+    let reply = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        state.handle(&parts, &body)
+    };
+    let res = Response::builder()
+        .status(reply.status)
+        .header("Content-Type", reply.content_type)
+        .body(Full::new(Bytes::from(reply.body)))
+        .unwrap_or_default();
+    Ok(res)
+}
+
+fn envelope(status: StatusCode, result: Value, result_info: Option<Value>) -> Reply {
+    let mut body = json!({
+        "result": result,
+        "success": true,
+        "errors": [],
+        "messages": [],
+    });
+    if let Some(info) = result_info {
+        body["result_info"] = info;
+    }
+    Reply {
+        status,
+        body: body.to_string(),
+        content_type: "application/json",
+    }
+}
+
+fn ok(result: Value) -> Reply {
+    envelope(StatusCode::OK, result, None)
+}
+
+fn error(status: StatusCode, code: usize, message: &str) -> Reply {
+    Reply {
+        status,
+        body: json!({
+            "result": null,
+            "success": false,
+            "errors": [{ "code": code, "message": message }],
+            "messages": [],
+        })
+        .to_string(),
+        content_type: "application/json",
+    }
+}
+
+fn no_route() -> Reply {
+    error(StatusCode::NOT_FOUND, 7000, "No route for that URI")
+}
+
+fn zone_not_found(zone_id: &str) -> Reply {
+    error(
+        StatusCode::BAD_REQUEST,
+        7003,
+        &format!(
+            "Could not route to /zones/{}, perhaps your object identifier is invalid?",
+            zone_id
+        ),
+    )
+}
+
+fn record_not_found() -> Reply {
+    error(StatusCode::NOT_FOUND, 81044, "Record does not exist.")
+}
+
+fn invalid_body() -> Reply {
+    error(StatusCode::BAD_REQUEST, 9207, "Request body is invalid.")
+}
+
+fn paginate(
+    items: Vec<Value>,
+    query: &HashMap<String, String>,
+    default_per_page: usize,
+    max_per_page: usize,
+) -> Reply {
+    let page = query
+        .get("page")
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(1)
+        .max(1);
+    let per_page = query
+        .get("per_page")
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(default_per_page)
+        .clamp(1, max_per_page);
+    let total_count = items.len();
+    let page_items: Vec<Value> = items
+        .into_iter()
+        .skip((page - 1) * per_page)
+        .take(per_page)
+        .collect();
+    let info = json!({
+        "page": page,
+        "per_page": per_page,
+        "count": page_items.len(),
+        "total_count": total_count,
+        "total_pages": total_count.div_ceil(per_page),
+    });
+    envelope(StatusCode::OK, Value::Array(page_items), Some(info))
+}
+
+fn merge(target: &mut Value, patch: &Value) {
+    if let (Some(target), Some(patch)) = (target.as_object_mut(), patch.as_object()) {
+        for (k, v) in patch {
+            target.insert(k.clone(), v.clone());
+        }
+    }
+}
+
+fn fqdn(name: &str, zone: &str) -> String {
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    if name.is_empty() || name == "@" {
+        zone.to_string()
+    } else if name == zone || name.ends_with(&format!(".{}", zone)) {
+        name
+    } else {
+        format!("{}.{}", name, zone)
+    }
+}
+
+fn text_op(haystack: &str, op: &str, needle: &str) -> Option<bool> {
+    match op {
+        "exact" => Some(haystack == needle),
+        "contains" => Some(haystack.contains(needle)),
+        "startswith" => Some(haystack.starts_with(needle)),
+        "endswith" => Some(haystack.ends_with(needle)),
+        _ => None,
+    }
+}
+
+/// Applies the record list filters other than `type`. Tag filters, which may repeat, are
+/// combined by `tag_match` and then count as one filter towards `match`, as in the real API.
+fn record_matches(record: &Value, pairs: &[(String, String)], zone_name: &str) -> bool {
+    let text = |field: &str| {
+        record[field]
+            .as_str()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    let tags: Vec<(String, String)> = record["tags"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(|t| {
+            let (name, value) = t.split_once(':').unwrap_or((t, ""));
+            (name.to_ascii_lowercase(), value.to_ascii_lowercase())
+        })
+        .collect();
+
+    let mut results = vec![];
+    let mut tag_results = vec![];
+    for (key, value) in pairs {
+        let needle = value.to_ascii_lowercase();
+        let (field, op) = key.split_once('.').unwrap_or((key.as_str(), ""));
+        match (field, op) {
+            ("name", "" | "exact") => results.push(text("name") == fqdn(value, zone_name)),
+            ("content" | "comment", "") => results.push(text(field) == needle),
+            ("comment", "present") => results.push(!text("comment").is_empty()),
+            ("comment", "absent") => results.push(text("comment").is_empty()),
+            ("name" | "content" | "comment", op) => {
+                if let Some(m) = text_op(&text(field), op, &needle) {
+                    results.push(m);
+                }
+            }
+            ("tag", "present") => tag_results.push(tags.iter().any(|(n, _)| *n == needle)),
+            ("tag", "absent") => tag_results.push(!tags.iter().any(|(n, _)| *n == needle)),
+            ("tag", op) => {
+                let (name, wanted) = needle.split_once(':').unwrap_or((&needle, ""));
+                let op = if op.is_empty() { "exact" } else { op };
+                tag_results.push(
+                    tags.iter()
+                        .any(|(n, v)| n == name && text_op(v, op, wanted) == Some(true)),
+                );
+            }
+            ("proxied", "") => results.push(record["proxied"] == (needle == "true")),
+            ("search", "") => results.push(
+                ["name", "content", "comment"]
+                    .iter()
+                    .any(|f| text(f).contains(&needle)),
+            ),
+            _ => {}
+        }
+    }
+    let any = |key: &str| pairs.iter().any(|(k, m)| k == key && m == "any");
+    if !tag_results.is_empty() {
+        results.push(if any("tag_match") {
+            tag_results.iter().any(|r| *r)
+        } else {
+            tag_results.iter().all(|r| *r)
+        });
+    }
+    if any("match") {
+        results.is_empty() || results.iter().any(|r| *r)
+    } else {
+        results.iter().all(|r| *r)
+    }
+}
+
+/// Current time as RFC 3339, without pulling in a date crate.
+fn now() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+impl State {
+    fn handle(&mut self, parts: &hyper::http::request::Parts, body: &Bytes) -> Reply {
+        let path = parts.uri.path();
+        let query_string = parts.uri.query().unwrap_or_default();
+        let pairs: Vec<(String, String)> = url::form_urlencoded::parse(query_string.as_bytes())
+            .into_owned()
+            .collect();
+        let query: HashMap<String, String> = pairs.iter().cloned().collect();
+        let rel = path.strip_prefix("/client/v4").unwrap_or(path);
+        self.requests.push(match query_string {
+            "" => format!("{} {}", parts.method, rel),
+            q => format!("{} {}?{}", parts.method, rel, q),
+        });
+
+        if !self.failures.is_empty() {
+            return self.failures.remove(0);
+        }
+        let authorized = parts
+            .headers
+            .get("Authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|t| t == self.token);
+        if !authorized {
+            return error(StatusCode::FORBIDDEN, 10000, "Authentication error");
+        }
+        if !path.starts_with("/client/v4/") {
+            return no_route();
+        }
+
+        let body: Value = if body.is_empty() {
+            Value::Null
+        } else {
+            match serde_json::from_slice(body) {
+                Ok(v) => v,
+                Err(_) => return invalid_body(),
+            }
+        };
+        let segments: Vec<&str> = rel.trim_matches('/').split('/').collect();
+        let method = parts.method.clone();
+        match (method, segments.as_slice()) {
+            (Method::GET, ["user", "tokens", "verify"]) => ok(json!({
+                "id": format!("{:032x}", 0),
+                "status": "active",
+            })),
+            (Method::GET, ["zones"]) => self.list_zones(&query),
+            (Method::POST, ["zones"]) => self.create_zone(&body),
+            (Method::GET, ["zones", zone_id]) => match self.zone(zone_id) {
+                Some(zone) => ok(zone),
+                None => zone_not_found(zone_id),
+            },
+            (Method::PATCH, ["zones", zone_id]) => self.edit_zone(zone_id, &body),
+            (Method::DELETE, ["zones", zone_id]) => self.delete_zone(zone_id),
+            (method, ["zones", zone_id, "dns_records", rest @ ..]) => {
+                let Some(zone) = self.zone(zone_id) else {
+                    return zone_not_found(zone_id);
+                };
+                match (method, rest) {
+                    (Method::GET, []) => self.list_records(&zone, &query, &pairs),
+                    (Method::POST, []) => self.create_record(&zone, body),
+                    (Method::GET, ["export"]) => self.export(&zone),
+                    (Method::POST, ["batch"]) => self.batch(&zone, &body),
+                    (Method::GET, [id]) => match self.record_index(&zone, id) {
+                        Some(i) => ok(self.records[i].clone()),
+                        None => record_not_found(),
+                    },
+                    (Method::PUT, [id]) => self.replace_record(&zone, id, body),
+                    (Method::PATCH, [id]) => self.update_record(&zone, id, &body),
+                    (Method::DELETE, [id]) => match self.record_index(&zone, id) {
+                        Some(i) => {
+                            let record = self.records.remove(i);
+                            ok(json!({ "id": record["id"] }))
+                        }
+                        None => record_not_found(),
+                    },
+                    _ => no_route(),
+                }
+            }
+            _ => no_route(),
+        }
+    }
+
+    fn next_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("{:032x}", self.next_id)
+    }
+
+    fn zone(&self, zone_id: &str) -> Option<Value> {
+        self.zones.iter().find(|z| z["id"] == zone_id).cloned()
+    }
+
+    fn new_zone(&mut self, name: &str, status: &str, account: Value) -> Value {
+        let ts = now();
+        json!({
+            "id": self.next_id(),
+            "name": name.trim_end_matches('.').to_ascii_lowercase(),
+            "status": status,
+            "paused": false,
+            "type": "full",
+            "development_mode": 0,
+            "account": account,
+            "name_servers": NAME_SERVERS,
+            "original_name_servers": [],
+            "created_on": ts,
+            "modified_on": ts,
+            "activated_on": if status == "active" { Value::String(ts.clone()) } else { Value::Null },
+        })
+    }
+
+    fn list_zones(&self, query: &HashMap<String, String>) -> Reply {
+        let zones: Vec<Value> = self
+            .zones
+            .iter()
+            .filter(|z| query.get("name").is_none_or(|n| z["name"] == n.as_str()))
+            .filter(|z| {
+                query
+                    .get("status")
+                    .is_none_or(|s| z["status"] == s.as_str())
+            })
+            .filter(|z| {
+                query
+                    .get("account.id")
+                    .is_none_or(|a| z["account"]["id"] == a.as_str())
+            })
+            .filter(|z| {
+                query
+                    .get("account.name")
+                    .is_none_or(|a| z["account"]["name"] == a.as_str())
+            })
+            .cloned()
+            .collect();
+        paginate(zones, query, DEFAULT_ZONES_PER_PAGE, MAX_ZONES_PER_PAGE)
+    }
+
+    fn create_zone(&mut self, body: &Value) -> Reply {
+        let Some(name) = body["name"].as_str() else {
+            return error(
+                StatusCode::BAD_REQUEST,
+                1002,
+                "Invalid or missing zone name.",
+            );
+        };
+        let name = name.trim_end_matches('.').to_ascii_lowercase();
+        if !name.contains('.') || name.split('.').any(str::is_empty) {
+            return error(
+                StatusCode::BAD_REQUEST,
+                1002,
+                "Invalid or missing zone name.",
+            );
+        }
+        if self.zones.iter().any(|z| z["name"] == name.as_str()) {
+            return error(
+                StatusCode::BAD_REQUEST,
+                1061,
+                &format!("{} already exists", name),
+            );
+        }
+        let mut zone = self.new_zone(&name, "pending", body["account"].clone());
+        if let Some(zone_type) = body["type"].as_str() {
+            zone["type"] = json!(zone_type);
+        }
+        self.zones.push(zone.clone());
+        ok(zone)
+    }
+
+    fn edit_zone(&mut self, zone_id: &str, body: &Value) -> Reply {
+        let Some(zone) = self.zones.iter_mut().find(|z| z["id"] == zone_id) else {
+            return zone_not_found(zone_id);
+        };
+        for field in ["paused", "type", "vanity_name_servers"] {
+            if !body[field].is_null() {
+                zone[field] = body[field].clone();
+            }
+        }
+        zone["modified_on"] = json!(now());
+        ok(zone.clone())
+    }
+
+    fn delete_zone(&mut self, zone_id: &str) -> Reply {
+        if self.zone(zone_id).is_none() {
+            return zone_not_found(zone_id);
+        }
+        self.zones.retain(|z| z["id"] != zone_id);
+        self.records.retain(|r| r["zone_id"] != zone_id);
+        ok(json!({ "id": zone_id }))
+    }
+
+    fn record_index(&self, zone: &Value, id: &str) -> Option<usize> {
+        self.records
+            .iter()
+            .position(|r| r["zone_id"] == zone["id"] && r["id"] == id)
+    }
+
+    fn list_records(
+        &self,
+        zone: &Value,
+        query: &HashMap<String, String>,
+        pairs: &[(String, String)],
+    ) -> Reply {
+        let zone_name = zone["name"].as_str().unwrap_or_default();
+        let mut records: Vec<Value> = self
+            .records
+            .iter()
+            .filter(|r| r["zone_id"] == zone["id"])
+            .filter(|r| {
+                query
+                    .get("type")
+                    .is_none_or(|t| r["type"] == t.to_ascii_uppercase().as_str())
+            })
+            .filter(|r| record_matches(r, pairs, zone_name))
+            .cloned()
+            .collect();
+        if let Some(order) = query.get("order") {
+            records.sort_by_key(|r| r[order.as_str()].to_string());
+            if query.get("direction").is_some_and(|d| d == "desc") {
+                records.reverse();
+            }
+        }
+        paginate(
+            records,
+            query,
+            DEFAULT_RECORDS_PER_PAGE,
+            MAX_RECORDS_PER_PAGE,
+        )
+    }
+
+    /// Normalizes and validates `record` the way the API does, without storing it. `existing`
+    /// is the record being replaced, whose identity and creation time carry over.
+    fn build_record(
+        &self,
+        zone: &Value,
+        mut record: Value,
+        existing: Option<&Value>,
+    ) -> Result<Value, Reply> {
+        let Some(fields) = record.as_object_mut() else {
+            return Err(invalid_body());
+        };
+        let record_type = fields
+            .get("type")
+            .and_then(Value::as_str)
+            .map(str::to_ascii_uppercase)
+            .unwrap_or_default();
+        if !RECORD_TYPES.contains(&record_type.as_str()) {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                9000,
+                "DNS record type is invalid.",
+            ));
+        }
+        let zone_name = zone["name"].as_str().unwrap_or_default();
+        let Some(name) = fields.get("name").and_then(Value::as_str) else {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                9007,
+                "DNS record name is required.",
+            ));
+        };
+        let name = fqdn(name, zone_name);
+        if fields.get("content").is_none_or(Value::is_null) {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                9005,
+                "DNS record content is required.",
+            ));
+        }
+        let proxiable = matches!(record_type.as_str(), "A" | "AAAA" | "CNAME");
+        if fields.get("proxied") == Some(&Value::Bool(true)) && !proxiable {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                9004,
+                &format!("{} records cannot be proxied.", record_type),
+            ));
+        }
+
+        let ts = now();
+        let mut out = Map::new();
+        out.insert("ttl".to_string(), json!(1));
+        out.insert("proxied".to_string(), json!(false));
+        out.insert("tags".to_string(), json!([]));
+        out.extend(std::mem::take(fields));
+        out.insert("type".to_string(), json!(record_type));
+        out.insert("name".to_string(), json!(name));
+        out.insert("zone_id".to_string(), zone["id"].clone());
+        out.insert("zone_name".to_string(), json!(zone_name));
+        out.insert("proxiable".to_string(), json!(proxiable));
+        out.insert("modified_on".to_string(), json!(ts));
+        match existing {
+            Some(existing) => {
+                out.insert("id".to_string(), existing["id"].clone());
+                out.insert("created_on".to_string(), existing["created_on"].clone());
+            }
+            None => {
+                out.insert("id".to_string(), Value::Null);
+                out.insert("created_on".to_string(), json!(ts));
+            }
+        }
+        let record = Value::Object(out);
+
+        if let Err(e) = serde_json::from_value::<RecordMessage>(record.clone()) {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                9005,
+                &format!("Content for {} record is invalid: {}", record_type, e),
+            ));
+        }
+        let duplicate = self.records.iter().any(|r| {
+            r["zone_id"] == record["zone_id"]
+                && r["id"] != record["id"]
+                && r["type"] == record["type"]
+                && r["name"] == record["name"]
+                && r["content"] == record["content"]
+        });
+        if duplicate {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                81058,
+                "An identical record already exists.",
+            ));
+        }
+        Ok(record)
+    }
+
+    fn new_record(&mut self, zone: &Value, body: Value) -> Result<Value, Reply> {
+        let mut record = self.build_record(zone, body, None)?;
+        record["id"] = json!(self.next_id());
+        Ok(record)
+    }
+
+    fn create_record(&mut self, zone: &Value, body: Value) -> Reply {
+        match self.new_record(zone, body) {
+            Ok(record) => {
+                self.records.push(record.clone());
+                ok(record)
+            }
+            Err(e) => e,
+        }
+    }
+
+    fn replace_record(&mut self, zone: &Value, id: &str, body: Value) -> Reply {
+        let Some(i) = self.record_index(zone, id) else {
+            return record_not_found();
+        };
+        match self.build_record(zone, body, Some(&self.records[i])) {
+            Ok(record) => {
+                self.records[i] = record.clone();
+                ok(record)
+            }
+            Err(e) => e,
+        }
+    }
+
+    fn update_record(&mut self, zone: &Value, id: &str, body: &Value) -> Reply {
+        let Some(i) = self.record_index(zone, id) else {
+            return record_not_found();
+        };
+        if !body.is_object() {
+            return invalid_body();
+        }
+        let mut merged = self.records[i].clone();
+        merge(&mut merged, body);
+        match self.build_record(zone, merged, Some(&self.records[i])) {
+            Ok(record) => {
+                self.records[i] = record.clone();
+                ok(record)
+            }
+            Err(e) => e,
+        }
+    }
+
+    fn export(&self, zone: &Value) -> Reply {
+        let records: Vec<RecordMessage> = self
+            .records
+            .iter()
+            .filter(|r| r["zone_id"] == zone["id"])
+            .filter_map(|r| serde_json::from_value(r.clone()).ok())
+            .collect();
+        Reply {
+            status: StatusCode::OK,
+            body: to_zone_file(&records, zone["name"].as_str()),
+            content_type: "text/plain",
+        }
+    }
+
+    /// Applies deletes, patches, puts then posts, all or nothing.
+    fn batch(&mut self, zone: &Value, body: &Value) -> Reply {
+        let saved_records = self.records.clone();
+        let saved_next_id = self.next_id;
+        match self.apply_batch(zone, body) {
+            Ok(result) => ok(result),
+            Err(e) => {
+                self.records = saved_records;
+                self.next_id = saved_next_id;
+                e
+            }
+        }
+    }
+
+    fn apply_batch(&mut self, zone: &Value, body: &Value) -> Result<Value, Reply> {
+        let list = |key: &str| body[key].as_array().cloned().unwrap_or_default();
+        let id_of = |op: &Value| op["id"].as_str().unwrap_or_default().to_string();
+
+        let mut deletes = vec![];
+        for op in list("deletes") {
+            let i = self
+                .record_index(zone, &id_of(&op))
+                .ok_or_else(record_not_found)?;
+            deletes.push(self.records.remove(i));
+        }
+        let mut patches = vec![];
+        for op in list("patches") {
+            let i = self
+                .record_index(zone, &id_of(&op))
+                .ok_or_else(record_not_found)?;
+            let mut merged = self.records[i].clone();
+            merge(&mut merged, &op);
+            let record = self.build_record(zone, merged, Some(&self.records[i]))?;
+            self.records[i] = record.clone();
+            patches.push(record);
+        }
+        let mut puts = vec![];
+        for mut op in list("puts") {
+            let i = self
+                .record_index(zone, &id_of(&op))
+                .ok_or_else(record_not_found)?;
+            if let Some(fields) = op.as_object_mut() {
+                fields.remove("id");
+            }
+            let record = self.build_record(zone, op, Some(&self.records[i]))?;
+            self.records[i] = record.clone();
+            puts.push(record);
+        }
+        let mut posts = vec![];
+        for op in list("posts") {
+            let record = self.new_record(zone, op)?;
+            self.records.push(record.clone());
+            posts.push(record);
+        }
+        Ok(json!({
+            "deletes": deletes,
+            "patches": patches,
+            "puts": puts,
+            "posts": posts,
+        }))
+    }
+}
@@ -0,0 +1,514 @@
+use hyper::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AccountId, ApiError, ApiErrorKind, ApiResponse, ApiResult, Cloudflare, PerformOptions,
+    QueryParams, Timestamp, V4PagePaginationArray, ZoneId,
+};
+
+pub mod page_rules;
+pub mod settings;
+pub use page_rules::{
+    plan_priorities, CloudflarePageRules, EditPageRuleRequest, ForwardingUrl, ListPageRulesRequest,
+    PageRule, PageRuleAction, PageRuleCacheLevel, PageRuleConstraint, PageRuleOperator,
+    PageRuleOrder, PageRuleRequest, PageRuleSslMode, PageRuleStatus, PageRuleTarget, Polish,
+};
+pub use settings::{
+    CacheLevel, CloudflareZoneSettings, SecurityLevel, SslMode, Tls13, TlsVersion, Toggle,
+    ZoneSetting, ZoneSettingEntry, ZoneSettingId,
+};
+
+/// Statuses this crate does not know yet come through as `Other`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneStatus {
+    Initializing,
+    Pending,
+    Active,
+    Moved,
+    Deleted,
+    Deactivated,
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl ZoneStatus {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Initializing => "initializing",
+            Self::Pending => "pending",
+            Self::Active => "active",
+            Self::Moved => "moved",
+            Self::Deleted => "deleted",
+            Self::Deactivated => "deactivated",
+            Self::Other(status) => status,
+        }
+    }
+}
+
+/// Types this crate does not know yet come through as `Other`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneType {
+    #[default]
+    Full,
+    Partial,
+    Secondary,
+    Internal,
+    #[serde(untagged)]
+    Other(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ZonePlan {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legacy_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_subscribed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_subscribe: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legacy_discount: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub externally_managed: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ZoneMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cdn_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foundation_dns: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_certificate_quota: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_rule_quota: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phishing_detected: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ZoneOwner {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub owner_type: Option<String>,
+}
+
+/// Nameservers assigned to a zone, alongside the ones it was using before it moved to
+/// Cloudflare.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ZoneNameServers {
+    pub name_servers: Vec<String>,
+    pub original_name_servers: Vec<String>,
+    pub vanity_name_servers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_registrar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_dnshost: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ZoneAccount {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Zone {
+    pub id: ZoneId,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ZoneStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub zone_type: Option<ZoneType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub development_mode: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<ZoneAccount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<ZoneOwner>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<ZonePlan>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ZoneMeta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_servers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_name_servers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vanity_name_servers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_registrar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_dnshost: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activated_on: Option<Timestamp>,
+}
+
+impl Zone {
+    pub fn name_server_assignment(&self) -> ZoneNameServers {
+        ZoneNameServers {
+            name_servers: self.name_servers.clone().unwrap_or_default(),
+            original_name_servers: self.original_name_servers.clone().unwrap_or_default(),
+            vanity_name_servers: self.vanity_name_servers.clone().unwrap_or_default(),
+            original_registrar: self.original_registrar.clone(),
+            original_dnshost: self.original_dnshost.clone(),
+        }
+    }
+}
+
+pub type ListZonesResponse = V4PagePaginationArray<Zone>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateZoneRequest {
+    pub name: String,
+    pub account: ZoneAccount,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub zone_type: Option<ZoneType>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EditZoneRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub zone_type: Option<ZoneType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vanity_name_servers: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ZoneIdResult {
+    pub id: ZoneId,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ListZonesRequest {
+    pub name: Option<String>,
+    pub status: Option<ZoneStatus>,
+    pub account_id: Option<AccountId>,
+    pub account_name: Option<String>,
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+impl ListZonesRequest {
+    fn params(&self) -> QueryParams {
+        let mut params = vec![];
+        if let Some(name) = &self.name {
+            params.push(("name".to_string(), name.clone()));
+        }
+        if let Some(status) = &self.status {
+            params.push(("status".to_string(), status.as_str().to_string()));
+        }
+        if let Some(account_id) = &self.account_id {
+            params.push(("account.id".to_string(), account_id.to_string()));
+        }
+        if let Some(account_name) = &self.account_name {
+            params.push(("account.name".to_string(), account_name.clone()));
+        }
+        if let Some(page) = self.page {
+            params.push(("page".to_string(), page.to_string()));
+        }
+        if let Some(per_page) = self.per_page {
+            params.push(("per_page".to_string(), per_page.to_string()));
+        }
+        params
+    }
+}
+
+pub struct CloudflareZones {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareZones {
+    pub fn page_rules(&self) -> CloudflarePageRules {
+        CloudflarePageRules { c: self.c.clone() }
+    }
+
+    pub fn settings(&self) -> CloudflareZoneSettings {
+        CloudflareZoneSettings { c: self.c.clone() }
+    }
+
+    pub async fn list_zones(&self, req: ListZonesRequest) -> ApiResult<ListZonesResponse> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: "zones".to_string(),
+                    params: Some(req.params()),
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn get_zone(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Zone>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn create_zone(&self, req: CreateZoneRequest) -> ApiResult<ApiResponse<Zone>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::POST,
+                PerformOptions {
+                    path: "zones".to_string(),
+                    params: None,
+                    body: Some(req),
+                },
+            )
+            .await
+    }
+
+    pub async fn edit_zone(
+        &self,
+        zone_id: &ZoneId,
+        req: EditZoneRequest,
+    ) -> ApiResult<ApiResponse<Zone>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::PATCH,
+                PerformOptions {
+                    path: format!("zones/{}", zone_id),
+                    params: None,
+                    body: Some(req),
+                },
+            )
+            .await
+    }
+
+    pub async fn delete_zone(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<ZoneIdResult>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::DELETE,
+                PerformOptions::<()> {
+                    path: format!("zones/{}", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn pause_zone(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Zone>> {
+        self.edit_zone(
+            zone_id,
+            EditZoneRequest {
+                paused: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    pub async fn unpause_zone(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Zone>> {
+        self.edit_zone(
+            zone_id,
+            EditZoneRequest {
+                paused: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Asks Cloudflare to re-check a pending zone's nameservers. The API rate limits this to
+    /// once every five minutes for free plans.
+    pub async fn activation_check(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<ZoneIdResult>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::PUT,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/activation_check", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn get_plan(&self, zone_id: &ZoneId) -> ApiResult<ZonePlan> {
+        let zone = self.get_zone(zone_id).await?.into_result()?;
+        match zone.plan {
+            Some(plan) => Ok(plan),
+            None => Err(ApiError::with_kind(
+                ApiErrorKind::NotFound,
+                format!("zone {} has no plan", zone_id),
+            )),
+        }
+    }
+
+    pub async fn get_name_servers(&self, zone_id: &ZoneId) -> ApiResult<ZoneNameServers> {
+        let zone = self.get_zone(zone_id).await?.into_result()?;
+        Ok(zone.name_server_assignment())
+    }
+
+    /// Finds the zone that owns `fqdn` by trying each parent domain in turn, from the full name
+    /// down to the registrable two-label suffix, so `www.api.example.com` resolves to the
+    /// `example.com` zone unless `api.example.com` is a zone of its own.
+    ///
+    /// With [`Cloudflare::with_zone_cache`], each candidate is answered from the cache when it
+    /// has been looked up before, whether it turned out to be a zone or not, so the result is
+    /// the same as without the cache.
+    pub async fn resolve_zone(&self, fqdn: &str) -> ApiResult<ZoneId> {
+        for candidate in zone_candidates(fqdn)? {
+            match self.c.cached_zone_id(&candidate) {
+                Some(Some(id)) => return Ok(id),
+                Some(None) => continue,
+                None => {}
+            }
+            let res = self
+                .list_zones(ListZonesRequest {
+                    name: Some(candidate.clone()),
+                    ..Default::default()
+                })
+                .await?;
+            let found = res
+                .into_result()?
+                .into_iter()
+                .find(|z| z.name.eq_ignore_ascii_case(&candidate));
+            self.c
+                .cache_zone_id(&candidate, found.as_ref().map(|z| &z.id));
+            if let Some(zone) = found {
+                return Ok(zone.id);
+            }
+        }
+
+        Err(ApiError::with_kind(
+            ApiErrorKind::NotFound,
+            format!("no zone found for {}", fqdn),
+        ))
+    }
+}
+
+/// The names that could be the zone for `fqdn`, most specific first.
+fn zone_candidates(fqdn: &str) -> ApiResult<Vec<String>> {
+    let fqdn = fqdn.trim_end_matches('.').to_ascii_lowercase();
+    let labels: Vec<&str> = fqdn.split('.').collect();
+    if labels.len() < 2 || labels.iter().any(|l| l.is_empty()) {
+        return Err(ApiError::with_kind(
+            ApiErrorKind::Invalid,
+            format!("invalid domain name: {}", fqdn),
+        ));
+    }
+    Ok((0..labels.len() - 1)
+        .map(|i| labels[i..].join("."))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_unknown_statuses_and_types() {
+        let zones: Vec<Zone> = serde_json::from_value(serde_json::json!([
+            {
+                "id": "023e105f4ecef8ad9ca31a8372d0c353",
+                "name": "example.com",
+                "status": "read only",
+                "type": "delegated"
+            },
+            {
+                "id": "123e105f4ecef8ad9ca31a8372d0c353",
+                "name": "example.org",
+                "status": "active",
+                "type": "partial"
+            }
+        ]))
+        .unwrap();
+        assert_eq!(
+            zones[0].status,
+            Some(ZoneStatus::Other("read only".to_string()))
+        );
+        assert_eq!(
+            zones[0].zone_type,
+            Some(ZoneType::Other("delegated".to_string()))
+        );
+        assert_eq!(zones[1].status, Some(ZoneStatus::Active));
+        assert_eq!(zones[1].zone_type, Some(ZoneType::Partial));
+        let json = serde_json::to_value(&zones[0]).unwrap();
+        assert_eq!(
+            (&json["status"], &json["type"]),
+            (&"read only".into(), &"delegated".into())
+        );
+    }
+
+    #[test]
+    fn candidates_run_from_the_full_name_down() {
+        assert_eq!(
+            zone_candidates("WWW.api.example.com.").unwrap(),
+            vec!["www.api.example.com", "api.example.com", "example.com"]
+        );
+        assert_eq!(zone_candidates("example.com").unwrap(), vec!["example.com"]);
+        for bad in ["com", "www..example.com", ""] {
+            assert_eq!(
+                zone_candidates(bad).unwrap_err().kind(),
+                ApiErrorKind::Invalid
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_parent_zone_skips_the_network() {
+        // Nothing listens here, so any request fails with a transport error.
+        let c = Cloudflare::default()
+            .with_base_url("http://127.0.0.1:9".parse().unwrap())
+            .with_zone_cache();
+        let id: ZoneId = "023e105f4ecef8ad9ca31a8372d0c353".parse().unwrap();
+        c.cache_zone_id("www.example.com", None);
+        c.cache_zone_id("example.com", Some(&id));
+        let other: ZoneId = "123e105f4ecef8ad9ca31a8372d0c353".parse().unwrap();
+        c.cache_zone_id("www.api.example.com", None);
+        c.cache_zone_id("api.example.com", Some(&other));
+
+        let zones = c.zones();
+        assert_eq!(zones.resolve_zone("www.example.com").await.unwrap(), id);
+        assert_eq!(
+            zones.resolve_zone("www.api.example.com").await.unwrap(),
+            other
+        );
+        let err = zones.resolve_zone("www.example.org").await.unwrap_err();
+        assert_eq!(err.kind(), ApiErrorKind::Transport);
+    }
+}
@@ -0,0 +1,505 @@
+use std::collections::HashMap;
+
+use hyper::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{SecurityLevel, SslMode, Toggle};
+use crate::{
+    ApiError, ApiErrorKind, ApiResponse, ApiResult, Cloudflare, IdResult, PageRuleId,
+    PerformOptions, QueryParams, SortDirection, Timestamp, ZoneId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PageRuleOperator {
+    Matches,
+    Equals,
+    Contains,
+    NotEqual,
+    NotContain,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PageRuleConstraint {
+    pub operator: PageRuleOperator,
+    /// URL pattern; `*` matches any run of characters, e.g. `*example.com/images/*`.
+    pub value: String,
+}
+
+/// The URLs a page rule applies to. `url` is the only target the API has.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "target", rename_all = "snake_case")]
+pub enum PageRuleTarget {
+    Url { constraint: PageRuleConstraint },
+}
+
+impl PageRuleTarget {
+    pub fn matches(pattern: &str) -> Self {
+        Self::Url {
+            constraint: PageRuleConstraint {
+                operator: PageRuleOperator::Matches,
+                value: pattern.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ForwardingUrl {
+    /// Target URL; `$1`, `$2`... refer to the wildcards of the target pattern.
+    pub url: String,
+    /// 301 or 302.
+    pub status_code: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PageRuleCacheLevel {
+    Bypass,
+    Basic,
+    Simplified,
+    Aggressive,
+    CacheEverything,
+}
+
+/// The zone's [`SslMode`]s, plus `origin_pull`, which only page rules accept.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PageRuleSslMode {
+    /// Strict, with the origin also requiring Cloudflare's client certificate.
+    OriginPull,
+    #[serde(untagged)]
+    Mode(SslMode),
+}
+
+impl From<SslMode> for PageRuleSslMode {
+    fn from(mode: SslMode) -> Self {
+        Self::Mode(mode)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Polish {
+    Off,
+    Lossless,
+    Lossy,
+}
+
+/// A page rule setting, serialized as the API's `{"id": ..., "value": ...}` pair.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "id", content = "value", rename_all = "snake_case")]
+pub enum PageRuleAction {
+    AlwaysUseHttps,
+    AutomaticHttpsRewrites(Toggle),
+    /// Seconds.
+    BrowserCacheTtl(u32),
+    BrowserCheck(Toggle),
+    /// Cookie name pattern, e.g. `session_*`.
+    BypassCacheOnCookie(String),
+    CacheByDeviceType(Toggle),
+    CacheDeceptionArmor(Toggle),
+    /// Kept raw; the cache key definition has many optional parts.
+    CacheKeyFields(Value),
+    CacheLevel(PageRuleCacheLevel),
+    CacheOnCookie(String),
+    DisableApps,
+    DisablePerformance,
+    DisableSecurity,
+    DisableZaraz,
+    /// Seconds.
+    EdgeCacheTtl(u32),
+    EmailObfuscation(Toggle),
+    ExplicitCacheControl(Toggle),
+    /// Redirects matching URLs. Cannot be combined with other actions.
+    ForwardingUrl(ForwardingUrl),
+    HostHeaderOverride(String),
+    IpGeolocation(Toggle),
+    Mirage(Toggle),
+    OpportunisticEncryption(Toggle),
+    OriginErrorPagePassThru(Toggle),
+    Polish(Polish),
+    ResolveOverride(String),
+    RespectStrongEtag(Toggle),
+    ResponseBuffering(Toggle),
+    RocketLoader(Toggle),
+    SecurityLevel(SecurityLevel),
+    ServerSideExclude(Toggle),
+    SortQueryStringForCache(Toggle),
+    Ssl(PageRuleSslMode),
+    TrueClientIpHeader(Toggle),
+    Waf(Toggle),
+    /// Settings this crate has no variant for, or whose value does not decode, kept as sent
+    /// so that updates pass them back unchanged.
+    #[serde(untagged)]
+    Other {
+        id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<Value>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PageRuleStatus {
+    #[default]
+    Active,
+    Disabled,
+}
+
+impl PageRuleStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Disabled => "disabled",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PageRule {
+    pub id: PageRuleId,
+    pub targets: Vec<PageRuleTarget>,
+    pub actions: Vec<PageRuleAction>,
+    /// Higher numbers win when several rules match a URL.
+    pub priority: u32,
+    pub status: PageRuleStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_on: Option<Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PageRuleRequest {
+    pub targets: Vec<PageRuleTarget>,
+    pub actions: Vec<PageRuleAction>,
+    /// Defaults to 1, the lowest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<PageRuleStatus>,
+}
+
+impl PageRuleRequest {
+    pub fn new(pattern: &str, actions: Vec<PageRuleAction>) -> Self {
+        Self {
+            targets: vec![PageRuleTarget::matches(pattern)],
+            actions,
+            priority: None,
+            status: None,
+        }
+    }
+}
+
+impl From<PageRule> for PageRuleRequest {
+    fn from(rule: PageRule) -> Self {
+        Self {
+            targets: rule.targets,
+            actions: rule.actions,
+            priority: Some(rule.priority),
+            status: Some(rule.status),
+        }
+    }
+}
+
+/// Partial update; fields left empty are kept.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EditPageRuleRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub targets: Option<Vec<PageRuleTarget>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions: Option<Vec<PageRuleAction>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<PageRuleStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageRuleOrder {
+    Status,
+    Priority,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ListPageRulesRequest {
+    pub status: Option<PageRuleStatus>,
+    pub order: Option<(PageRuleOrder, SortDirection)>,
+}
+
+impl ListPageRulesRequest {
+    fn params(&self) -> QueryParams {
+        let mut params = vec![];
+        if let Some(status) = &self.status {
+            params.push(("status".to_string(), status.as_str().to_string()));
+        }
+        if let Some((order, direction)) = &self.order {
+            let order = match order {
+                PageRuleOrder::Status => "status",
+                PageRuleOrder::Priority => "priority",
+            };
+            let direction = match direction {
+                SortDirection::Asc => "asc",
+                SortDirection::Desc => "desc",
+            };
+            params.push(("order".to_string(), order.to_string()));
+            params.push(("direction".to_string(), direction.to_string()));
+        }
+        params
+    }
+}
+
+/// The priority updates that put `rules` in `order`, first rule winning. `order` must name
+/// every rule exactly once.
+pub fn plan_priorities(
+    rules: &[PageRule],
+    order: &[PageRuleId],
+) -> ApiResult<Vec<(PageRuleId, u32)>> {
+    let current: HashMap<&PageRuleId, u32> = rules.iter().map(|r| (&r.id, r.priority)).collect();
+    let mut seen = std::collections::HashSet::new();
+    for id in order {
+        if !current.contains_key(id) {
+            return Err(ApiError::with_kind(
+                ApiErrorKind::Invalid,
+                format!("page rule {} is not in the zone", id),
+            ));
+        }
+        if !seen.insert(id) {
+            return Err(ApiError::with_kind(
+                ApiErrorKind::Invalid,
+                format!("page rule {} listed twice", id),
+            ));
+        }
+    }
+    if seen.len() != rules.len() {
+        return Err(ApiError::with_kind(
+            ApiErrorKind::Invalid,
+            format!(
+                "order names {} of the zone's {} page rules",
+                seen.len(),
+                rules.len()
+            ),
+        ));
+    }
+    let total = order.len() as u32;
+    Ok(order
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.clone(), total - i as u32))
+        .filter(|(id, priority)| current[id] != *priority)
+        .collect())
+}
+
+pub struct CloudflarePageRules {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflarePageRules {
+    async fn call<R, T>(
+        &self,
+        method: Method,
+        path: String,
+        params: Option<QueryParams>,
+        body: Option<R>,
+    ) -> ApiResult<ApiResponse<T>>
+    where
+        R: Serialize + Clone,
+        T: DeserializeOwned,
+    {
+        self.c
+            .clone()
+            .perform_json(method, PerformOptions { path, params, body })
+            .await
+    }
+
+    pub async fn list(
+        &self,
+        zone_id: &ZoneId,
+        req: &ListPageRulesRequest,
+    ) -> ApiResult<ApiResponse<Vec<PageRule>>> {
+        let path = format!("zones/{}/pagerules", zone_id);
+        self.call::<(), _>(Method::GET, path, Some(req.params()), None)
+            .await
+    }
+
+    pub async fn get(
+        &self,
+        zone_id: &ZoneId,
+        rule_id: &PageRuleId,
+    ) -> ApiResult<ApiResponse<PageRule>> {
+        let path = format!("zones/{}/pagerules/{}", zone_id, rule_id);
+        self.call::<(), _>(Method::GET, path, None, None).await
+    }
+
+    pub async fn create(
+        &self,
+        zone_id: &ZoneId,
+        req: PageRuleRequest,
+    ) -> ApiResult<ApiResponse<PageRule>> {
+        let path = format!("zones/{}/pagerules", zone_id);
+        self.call(Method::POST, path, None, Some(req)).await
+    }
+
+    pub async fn update(
+        &self,
+        zone_id: &ZoneId,
+        rule_id: &PageRuleId,
+        req: PageRuleRequest,
+    ) -> ApiResult<ApiResponse<PageRule>> {
+        let path = format!("zones/{}/pagerules/{}", zone_id, rule_id);
+        self.call(Method::PUT, path, None, Some(req)).await
+    }
+
+    pub async fn edit(
+        &self,
+        zone_id: &ZoneId,
+        rule_id: &PageRuleId,
+        req: EditPageRuleRequest,
+    ) -> ApiResult<ApiResponse<PageRule>> {
+        let path = format!("zones/{}/pagerules/{}", zone_id, rule_id);
+        self.call(Method::PATCH, path, None, Some(req)).await
+    }
+
+    pub async fn delete(
+        &self,
+        zone_id: &ZoneId,
+        rule_id: &PageRuleId,
+    ) -> ApiResult<ApiResponse<IdResult<PageRuleId>>> {
+        let path = format!("zones/{}/pagerules/{}", zone_id, rule_id);
+        self.call::<(), _>(Method::DELETE, path, None, None).await
+    }
+
+    /// Sets priorities so that rules apply in `order`, first rule winning, and returns the
+    /// rules in that order. Only rules whose priority changes are updated; a failure part
+    /// way leaves the earlier updates in place.
+    pub async fn reorder(
+        &self,
+        zone_id: &ZoneId,
+        order: &[PageRuleId],
+    ) -> ApiResult<Vec<PageRule>> {
+        let rules = self
+            .list(zone_id, &ListPageRulesRequest::default())
+            .await?
+            .into_result()?;
+        let mut by_id: HashMap<PageRuleId, PageRule> =
+            rules.iter().map(|r| (r.id.clone(), r.clone())).collect();
+        for (id, priority) in plan_priorities(&rules, order)? {
+            let req = EditPageRuleRequest {
+                priority: Some(priority),
+                ..Default::default()
+            };
+            let updated = self.edit(zone_id, &id, req).await?.into_result()?;
+            by_id.insert(id, updated);
+        }
+        Ok(order.iter().filter_map(|id| by_id.remove(id)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, priority: u32) -> PageRule {
+        PageRule {
+            id: id.parse().unwrap(),
+            targets: vec![PageRuleTarget::matches("*example.com/*")],
+            actions: vec![PageRuleAction::AlwaysUseHttps],
+            priority,
+            status: PageRuleStatus::Active,
+            created_on: None,
+            modified_on: None,
+        }
+    }
+
+    #[test]
+    fn encodes_targets_and_actions() {
+        let req = PageRuleRequest::new(
+            "*example.com/old/*",
+            vec![PageRuleAction::ForwardingUrl(ForwardingUrl {
+                url: "https://example.com/new/$1".to_string(),
+                status_code: 301,
+            })],
+        );
+        assert_eq!(
+            serde_json::to_value(&req).unwrap(),
+            serde_json::json!({
+                "targets": [{
+                    "target": "url",
+                    "constraint": {"operator": "matches", "value": "*example.com/old/*"}
+                }],
+                "actions": [{
+                    "id": "forwarding_url",
+                    "value": {"url": "https://example.com/new/$1", "status_code": 301}
+                }]
+            })
+        );
+
+        let actions: Vec<PageRuleAction> = serde_json::from_value(serde_json::json!([
+            {"id": "always_use_https"},
+            {"id": "cache_level", "value": "cache_everything"},
+            {"id": "edge_cache_ttl", "value": 7200},
+            {"id": "ssl", "value": "flexible"},
+            {"id": "security_level", "value": "under_attack"}
+        ]))
+        .unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                PageRuleAction::AlwaysUseHttps,
+                PageRuleAction::CacheLevel(PageRuleCacheLevel::CacheEverything),
+                PageRuleAction::EdgeCacheTtl(7200),
+                PageRuleAction::Ssl(SslMode::Flexible.into()),
+                PageRuleAction::SecurityLevel(SecurityLevel::UnderAttack),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_actions_raw() {
+        let raw = serde_json::json!([
+            {"id": "minify", "value": {"css": "on", "html": "off", "js": "on"}},
+            {"id": "disable_railgun"},
+            {"id": "cache_ttl_by_status", "value": {"200-299": 3600, "404": 0}},
+            {"id": "automatic_platform_optimization", "value": "on"},
+            {"id": "ssl", "value": "origin_pull"},
+            {"id": "edge_cache_ttl", "value": "soon"}
+        ]);
+        let actions: Vec<PageRuleAction> = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(
+            actions[1],
+            PageRuleAction::Other {
+                id: "disable_railgun".to_string(),
+                value: None
+            }
+        );
+        assert!(
+            matches!(&actions[2], PageRuleAction::Other { id, value: Some(_) } if id == "cache_ttl_by_status")
+        );
+        assert_eq!(actions[4], PageRuleAction::Ssl(PageRuleSslMode::OriginPull));
+        assert!(serde_json::from_value::<SslMode>(serde_json::json!("origin_pull")).is_err());
+        assert!(matches!(&actions[5], PageRuleAction::Other { id, .. } if id == "edge_cache_ttl"));
+        assert_eq!(serde_json::to_value(&actions).unwrap(), raw);
+    }
+
+    #[test]
+    fn plans_priorities() {
+        let a = rule("1f2e3d4c5b6a79881f2e3d4c5b6a7988", 3);
+        let b = rule("2f2e3d4c5b6a79881f2e3d4c5b6a7988", 2);
+        let c = rule("3f2e3d4c5b6a79881f2e3d4c5b6a7988", 1);
+        let rules = vec![a.clone(), b.clone(), c.clone()];
+
+        let swap = plan_priorities(&rules, &[a.id.clone(), c.id.clone(), b.id.clone()]).unwrap();
+        assert_eq!(swap, vec![(c.id.clone(), 2), (b.id.clone(), 1)]);
+        assert!(
+            plan_priorities(&rules, &[a.id.clone(), b.id.clone(), c.id.clone()])
+                .unwrap()
+                .is_empty()
+        );
+
+        assert!(plan_priorities(&rules, &[a.id.clone(), b.id.clone()]).is_err());
+        let twice = plan_priorities(&rules, &[a.id.clone(), a.id.clone(), b.id.clone()]);
+        assert_eq!(twice.unwrap_err().kind(), ApiErrorKind::Invalid);
+    }
+}
@@ -0,0 +1,338 @@
+use hyper::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{ApiResponse, ApiResult, Cloudflare, PerformOptions, Timestamp, ZoneId};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Toggle {
+    On,
+    Off,
+}
+
+impl From<bool> for Toggle {
+    fn from(on: bool) -> Self {
+        if on {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+}
+
+/// Encryption between Cloudflare and the origin.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SslMode {
+    Off,
+    Flexible,
+    Full,
+    Strict,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TlsVersion {
+    #[serde(rename = "1.0")]
+    Tls1_0,
+    #[serde(rename = "1.1")]
+    Tls1_1,
+    #[serde(rename = "1.2")]
+    Tls1_2,
+    #[serde(rename = "1.3")]
+    Tls1_3,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Tls13 {
+    On,
+    Off,
+    /// TLS 1.3 with 0-RTT.
+    Zrt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityLevel {
+    Off,
+    EssentiallyOff,
+    Low,
+    Medium,
+    High,
+    UnderAttack,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheLevel {
+    Basic,
+    Simplified,
+    Aggressive,
+}
+
+/// A zone setting with its value, serialized as the API's `{"id": ..., "value": ...}` pair.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "id", content = "value", rename_all = "snake_case")]
+pub enum ZoneSetting {
+    Ssl(SslMode),
+    AlwaysUseHttps(Toggle),
+    AutomaticHttpsRewrites(Toggle),
+    MinTlsVersion(TlsVersion),
+    #[serde(rename = "tls_1_3")]
+    Tls13(Tls13),
+    Http3(Toggle),
+    #[serde(rename = "0rtt")]
+    ZeroRtt(Toggle),
+    Brotli(Toggle),
+    /// Seconds; `0` respects the origin's cache headers.
+    BrowserCacheTtl(u32),
+    CacheLevel(CacheLevel),
+    SecurityLevel(SecurityLevel),
+    /// Bypasses the cache for three hours, after which Cloudflare turns it off again.
+    DevelopmentMode(Toggle),
+    AlwaysOnline(Toggle),
+    EmailObfuscation(Toggle),
+    OpportunisticEncryption(Toggle),
+    Ipv6(Toggle),
+    Websockets(Toggle),
+}
+
+impl ZoneSetting {
+    pub fn id(&self) -> ZoneSettingId {
+        match self {
+            Self::Ssl(_) => ZoneSettingId::Ssl,
+            Self::AlwaysUseHttps(_) => ZoneSettingId::AlwaysUseHttps,
+            Self::AutomaticHttpsRewrites(_) => ZoneSettingId::AutomaticHttpsRewrites,
+            Self::MinTlsVersion(_) => ZoneSettingId::MinTlsVersion,
+            Self::Tls13(_) => ZoneSettingId::Tls13,
+            Self::Http3(_) => ZoneSettingId::Http3,
+            Self::ZeroRtt(_) => ZoneSettingId::ZeroRtt,
+            Self::Brotli(_) => ZoneSettingId::Brotli,
+            Self::BrowserCacheTtl(_) => ZoneSettingId::BrowserCacheTtl,
+            Self::CacheLevel(_) => ZoneSettingId::CacheLevel,
+            Self::SecurityLevel(_) => ZoneSettingId::SecurityLevel,
+            Self::DevelopmentMode(_) => ZoneSettingId::DevelopmentMode,
+            Self::AlwaysOnline(_) => ZoneSettingId::AlwaysOnline,
+            Self::EmailObfuscation(_) => ZoneSettingId::EmailObfuscation,
+            Self::OpportunisticEncryption(_) => ZoneSettingId::OpportunisticEncryption,
+            Self::Ipv6(_) => ZoneSettingId::Ipv6,
+            Self::Websockets(_) => ZoneSettingId::Websockets,
+        }
+    }
+
+    fn value(&self) -> Value {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|mut v| v.get_mut("value").map(Value::take))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneSettingId {
+    Ssl,
+    AlwaysUseHttps,
+    AutomaticHttpsRewrites,
+    MinTlsVersion,
+    Tls13,
+    Http3,
+    ZeroRtt,
+    Brotli,
+    BrowserCacheTtl,
+    CacheLevel,
+    SecurityLevel,
+    DevelopmentMode,
+    AlwaysOnline,
+    EmailObfuscation,
+    OpportunisticEncryption,
+    Ipv6,
+    Websockets,
+}
+
+impl ZoneSettingId {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ssl => "ssl",
+            Self::AlwaysUseHttps => "always_use_https",
+            Self::AutomaticHttpsRewrites => "automatic_https_rewrites",
+            Self::MinTlsVersion => "min_tls_version",
+            Self::Tls13 => "tls_1_3",
+            Self::Http3 => "http3",
+            Self::ZeroRtt => "0rtt",
+            Self::Brotli => "brotli",
+            Self::BrowserCacheTtl => "browser_cache_ttl",
+            Self::CacheLevel => "cache_level",
+            Self::SecurityLevel => "security_level",
+            Self::DevelopmentMode => "development_mode",
+            Self::AlwaysOnline => "always_online",
+            Self::EmailObfuscation => "email_obfuscation",
+            Self::OpportunisticEncryption => "opportunistic_encryption",
+            Self::Ipv6 => "ipv6",
+            Self::Websockets => "websockets",
+        }
+    }
+}
+
+/// A setting as returned by the API. The value is kept raw so that settings this crate has no
+/// variant for still come through; [`Self::setting`] gives the typed form.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ZoneSettingEntry {
+    pub id: String,
+    #[serde(default)]
+    pub value: Value,
+    /// `false` for settings the zone's plan does not allow changing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_on: Option<Timestamp>,
+    /// Seconds until a temporary setting, such as development mode, turns itself off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_remaining: Option<u64>,
+}
+
+impl ZoneSettingEntry {
+    pub fn setting(&self) -> Option<ZoneSetting> {
+        let pair = serde_json::json!({ "id": self.id, "value": self.value });
+        serde_json::from_value(pair).ok()
+    }
+
+    pub fn is_editable(&self) -> bool {
+        self.editable.unwrap_or(true)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct EditSettingsRequest {
+    items: Vec<ZoneSetting>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct EditSettingRequest {
+    value: Value,
+}
+
+pub struct CloudflareZoneSettings {
+    pub(crate) c: Cloudflare,
+}
+
+impl CloudflareZoneSettings {
+    pub async fn list(&self, zone_id: &ZoneId) -> ApiResult<ApiResponse<Vec<ZoneSettingEntry>>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/settings", zone_id),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn get(
+        &self,
+        zone_id: &ZoneId,
+        id: ZoneSettingId,
+    ) -> ApiResult<ApiResponse<ZoneSettingEntry>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::GET,
+                PerformOptions::<()> {
+                    path: format!("zones/{}/settings/{}", zone_id, id.as_str()),
+                    params: None,
+                    body: None,
+                },
+            )
+            .await
+    }
+
+    pub async fn set(
+        &self,
+        zone_id: &ZoneId,
+        setting: ZoneSetting,
+    ) -> ApiResult<ApiResponse<ZoneSettingEntry>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::PATCH,
+                PerformOptions {
+                    path: format!("zones/{}/settings/{}", zone_id, setting.id().as_str()),
+                    params: None,
+                    body: Some(EditSettingRequest {
+                        value: setting.value(),
+                    }),
+                },
+            )
+            .await
+    }
+
+    /// Changes several settings in one request. The API applies all of them or none.
+    pub async fn patch(
+        &self,
+        zone_id: &ZoneId,
+        settings: Vec<ZoneSetting>,
+    ) -> ApiResult<ApiResponse<Vec<ZoneSettingEntry>>> {
+        self.c
+            .clone()
+            .perform_json(
+                Method::PATCH,
+                PerformOptions {
+                    path: format!("zones/{}/settings", zone_id),
+                    params: None,
+                    body: Some(EditSettingsRequest { items: settings }),
+                },
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_settings() {
+        let settings = vec![
+            ZoneSetting::Ssl(SslMode::Strict),
+            ZoneSetting::MinTlsVersion(TlsVersion::Tls1_2),
+            ZoneSetting::Tls13(Tls13::Zrt),
+            ZoneSetting::ZeroRtt(Toggle::On),
+            ZoneSetting::BrowserCacheTtl(14400),
+        ];
+        let body = serde_json::to_value(EditSettingsRequest {
+            items: settings.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"items": [
+                {"id": "ssl", "value": "strict"},
+                {"id": "min_tls_version", "value": "1.2"},
+                {"id": "tls_1_3", "value": "zrt"},
+                {"id": "0rtt", "value": "on"},
+                {"id": "browser_cache_ttl", "value": 14400},
+            ]})
+        );
+        for setting in &settings {
+            let entry: ZoneSettingEntry = serde_json::from_value(serde_json::json!({
+                "id": setting.id().as_str(),
+                "value": setting.value(),
+                "editable": true,
+            }))
+            .unwrap();
+            assert_eq!(entry.setting().as_ref(), Some(setting));
+        }
+
+        let unknown: ZoneSettingEntry = serde_json::from_value(serde_json::json!({
+            "id": "image_resizing",
+            "value": "off",
+            "editable": false,
+        }))
+        .unwrap();
+        assert_eq!(unknown.setting(), None);
+        assert!(!unknown.is_editable());
+    }
+}
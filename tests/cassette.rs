@@ -0,0 +1,93 @@
+use std::{net::Ipv4Addr, path::PathBuf, sync::Arc};
+
+use cloudflare_rs::{
+    testing::{Cassette, CassetteMode, FakeCloudflare},
+    ARecordRequest, ApiErrorKind, Cloudflare, RecordMessage, ZoneId,
+};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "cloudflare-rs-{}-{}.json",
+        name,
+        std::process::id()
+    ))
+}
+
+fn replay_client(cassette: &Arc<Cassette>) -> Cloudflare {
+    Cloudflare::default()
+        .with_base_url("http://127.0.0.1:9".parse().unwrap())
+        .with_cassette(cassette.clone())
+}
+
+#[tokio::test]
+async fn records_then_replays() {
+    let path = temp_path("record");
+    let fake = FakeCloudflare::start().await;
+    fake.set_token("very-secret-token");
+    let zone_id = fake.add_zone("example.com");
+
+    let cassette = Arc::new(Cassette::record(&path));
+    let c = fake.client().with_cassette(cassette.clone());
+    let created = c
+        .dns()
+        .create_record(
+            &zone_id,
+            ARecordRequest {
+                name: Some("www".to_string()),
+                content: Some(Ipv4Addr::new(192, 0, 2, 1)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let recorded = c.dns().list_all_records(&zone_id).await.unwrap();
+    let export = c.dns().export_records(&zone_id).await.unwrap();
+    cassette.save().unwrap();
+    drop(fake);
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(!saved.contains("very-secret-token"));
+    assert_eq!(cassette.interactions().len(), 3);
+
+    let cassette = Arc::new(Cassette::replay(&path).unwrap());
+    assert_eq!(cassette.mode(), CassetteMode::Replay);
+    let c = replay_client(&cassette);
+    let replayed = c
+        .dns()
+        .create_record(&zone_id, ARecordRequest::default())
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert_eq!(replayed.id(), created.id());
+    let listed = c.dns().list_all_records(&zone_id).await.unwrap();
+    assert_eq!(listed.len(), recorded.len());
+    assert_eq!(c.dns().export_records(&zone_id).await.unwrap(), export);
+    assert!(cassette.unused().is_empty());
+
+    let err = c.dns().list_all_records(&zone_id).await.unwrap_err();
+    assert_eq!(err.kind(), ApiErrorKind::Transport);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn decodes_captured_records() {
+    let fixture =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/list_records.json");
+    let cassette = Arc::new(Cassette::replay(fixture).unwrap());
+    let c = replay_client(&cassette);
+    let zone_id = ZoneId::try_from("023e105f4ecef8ad9ca31a8372d0c353").unwrap();
+
+    let records = c.dns().list_all_records(&zone_id).await.unwrap();
+    let types: Vec<&str> = records.iter().map(RecordMessage::record_type).collect();
+    assert_eq!(types, vec!["A", "AAAA", "CNAME", "MX", "TXT"]);
+    assert_eq!(records[0].comment(), Some("Apex"));
+    assert_eq!(records[0].tags(), Some(&vec!["owner:dns-team".to_string()]));
+    assert!(records.iter().all(|r| r.created_on().is_some()));
+    match &records[3] {
+        RecordMessage::MX(mx) => assert_eq!(mx.priority, Some(10)),
+        other => panic!("expected MX, got {:?}", other),
+    }
+}
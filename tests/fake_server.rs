@@ -0,0 +1,521 @@
+use std::net::Ipv4Addr;
+
+use cloudflare_rs::{
+    testing::FakeCloudflare, ARecordRequest, ApiErrorKind, CreateZoneRequest, DdnsStatus,
+    FixedIpSource, IpFamily, ListRecordsRequest, ListZonesRequest, MatchMode, Ownership,
+    RecordFilter, RecordId, RecordMessage, RecordRequest, TXTRecordRequest, TagFilter, TextMatch,
+    ZoneAccount,
+};
+
+fn a(name: &str, ip: [u8; 4]) -> ARecordRequest {
+    ARecordRequest {
+        name: Some(name.to_string()),
+        content: Some(Ipv4Addr::from(ip)),
+        ..Default::default()
+    }
+}
+
+fn txt(name: &str, content: &str) -> TXTRecordRequest {
+    TXTRecordRequest {
+        name: Some(name.to_string()),
+        content: Some(content.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn zone_lifecycle() {
+    let fake = FakeCloudflare::start().await;
+    let zones = fake.client().zones();
+
+    let zone = zones
+        .create_zone(CreateZoneRequest {
+            name: "example.com".to_string(),
+            account: ZoneAccount::default(),
+            zone_type: None,
+        })
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert_eq!(zone.name, "example.com");
+
+    let err = zones
+        .create_zone(CreateZoneRequest {
+            name: "example.com".to_string(),
+            account: ZoneAccount::default(),
+            zone_type: None,
+        })
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert!(err.message().contains("1061"), "{}", err);
+
+    assert_eq!(
+        zones.resolve_zone("www.api.example.com").await.unwrap(),
+        zone.id
+    );
+    let paused = zones
+        .pause_zone(&zone.id)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert_eq!(paused.paused, Some(true));
+
+    zones
+        .delete_zone(&zone.id)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let err = zones
+        .get_zone(&zone.id)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(err.kind(), ApiErrorKind::NotFound);
+    let err = zones.resolve_zone("www.example.com").await.unwrap_err();
+    assert_eq!(err.kind(), ApiErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn resolve_zone_uses_the_cache_for_subdomains() {
+    let fake = FakeCloudflare::start().await;
+    let zone_id = fake.add_zone("example.com");
+    let zones = fake.client().with_zone_cache().zones();
+
+    assert_eq!(
+        zones.resolve_zone("www.example.com").await.unwrap(),
+        zone_id
+    );
+    assert_eq!(
+        fake.requests(),
+        vec![
+            "GET /zones?name=www.example.com",
+            "GET /zones?name=example.com"
+        ]
+    );
+
+    assert_eq!(
+        zones.resolve_zone("www.example.com").await.unwrap(),
+        zone_id
+    );
+    assert_eq!(fake.requests().len(), 2);
+    assert_eq!(
+        zones.resolve_zone("mail.example.com").await.unwrap(),
+        zone_id
+    );
+    assert_eq!(
+        fake.requests().last().unwrap(),
+        "GET /zones?name=mail.example.com"
+    );
+    assert_eq!(fake.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn resolve_zone_finds_a_zone_nested_in_a_cached_one() {
+    let fake = FakeCloudflare::start().await;
+    let parent = fake.add_zone("example.com");
+    let nested = fake.add_zone("api.example.com");
+    let cached = fake.client().with_zone_cache().zones();
+    let uncached = fake.client().zones();
+
+    assert_eq!(
+        cached.resolve_zone("www.example.com").await.unwrap(),
+        parent
+    );
+    for zones in [&cached, &uncached] {
+        assert_eq!(
+            zones.resolve_zone("www.api.example.com").await.unwrap(),
+            nested
+        );
+        assert_eq!(zones.resolve_zone("api.example.com").await.unwrap(), nested);
+        assert_eq!(zones.resolve_zone("example.com").await.unwrap(), parent);
+    }
+
+    let before = fake.requests().len();
+    cached.resolve_zone("www.api.example.com").await.unwrap();
+    cached.resolve_zone("www.example.com").await.unwrap();
+    assert_eq!(fake.requests().len(), before);
+}
+
+#[tokio::test]
+async fn record_crud() {
+    let fake = FakeCloudflare::start().await;
+    let zone_id = fake.add_zone("example.com");
+    let dns = fake.client().dns();
+
+    let created = dns
+        .create_record(&zone_id, a("www", [192, 0, 2, 1]))
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let id = created.id().unwrap().clone();
+    let req = RecordRequest::from(created);
+    assert_eq!(req.name(), Some("www.example.com"));
+
+    let updated = dns
+        .update_record(&zone_id, &id, content_update())
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert_eq!(
+        RecordRequest::from(updated.clone()).content().as_deref(),
+        Some("192.0.2.2")
+    );
+    assert_eq!(updated.comment(), Some("updated"));
+
+    let fetched = dns
+        .get_record(&zone_id, &id)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert_eq!(fetched.comment(), Some("updated"));
+
+    dns.delete_record(&zone_id, &id)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    let err = dns
+        .get_record(&zone_id, &id)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(err.kind(), ApiErrorKind::NotFound);
+    assert!(fake.records(&zone_id).is_empty());
+}
+
+fn content_update() -> ARecordRequest {
+    ARecordRequest {
+        content: Some(Ipv4Addr::new(192, 0, 2, 2)),
+        comment: Some("updated".to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn lists_every_page() {
+    let fake = FakeCloudflare::start().await;
+    let zone_id = fake.add_zone("example.com");
+    for i in 0..250u32 {
+        fake.add_record(&zone_id, txt(&format!("r{}", i), "v"));
+    }
+    let dns = fake.client().dns();
+
+    let page = dns
+        .list_records(ListRecordsRequest::new(zone_id.clone()).with_page(3))
+        .await
+        .unwrap();
+    let info = page.result_info.as_ref().unwrap();
+    assert_eq!((info.count, info.total_count), (50, 250));
+
+    let all = dns.list_all_records(&zone_id).await.unwrap();
+    assert_eq!(all.len(), 250);
+    assert_eq!(
+        fake.requests()
+            .iter()
+            .filter(|r| r.starts_with("GET /zones/"))
+            .count(),
+        4
+    );
+}
+
+#[tokio::test]
+async fn reports_api_errors() {
+    let fake = FakeCloudflare::start().await;
+    let zone_id = fake.add_zone("example.com");
+    fake.add_record(&zone_id, a("www", [192, 0, 2, 1]));
+    let dns = fake.client().dns();
+
+    let err = dns
+        .create_record(&zone_id, a("www", [192, 0, 2, 1]))
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert!(err.message().contains("81058"), "{}", err);
+
+    let missing = RecordId::try_from("f".repeat(32)).unwrap();
+    let err = dns
+        .get_record(&zone_id, &missing)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(err.kind(), ApiErrorKind::NotFound);
+
+    fake.fail_next(429, 10429, "Rate limited");
+    let err = dns.list_all_records(&zone_id).await.unwrap_err();
+    assert_eq!(err.kind(), ApiErrorKind::RateLimited);
+
+    fake.set_token("rotated");
+    let err = dns.list_all_records(&zone_id).await.unwrap_err();
+    assert_eq!(err.kind(), ApiErrorKind::Auth);
+    assert!(fake.client().verify_token().await.is_ok());
+}
+
+#[tokio::test]
+async fn batch_is_atomic() {
+    let fake = FakeCloudflare::start().await;
+    let zone_id = fake.add_zone("example.com");
+    let old = fake.add_record(&zone_id, a("old", [192, 0, 2, 1]));
+    let dns = fake.client().dns();
+
+    let missing = RecordId::try_from("f".repeat(32)).unwrap();
+    let err = dns
+        .batch(zone_id.clone())
+        .delete(old.id().unwrap().clone())
+        .delete(missing)
+        .post(a("new", [192, 0, 2, 2]))
+        .submit()
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ApiErrorKind::NotFound);
+    assert_eq!(fake.records(&zone_id).len(), 1);
+
+    let result = dns
+        .batch(zone_id.clone())
+        .delete(old.id().unwrap().clone())
+        .post(a("new", [192, 0, 2, 2]))
+        .submit()
+        .await
+        .unwrap();
+    assert_eq!((result.deletes.len(), result.posts.len()), (1, 1));
+    let names: Vec<String> = fake
+        .records(&zone_id)
+        .into_iter()
+        .filter_map(|r| RecordRequest::from(r).name().map(str::to_string))
+        .collect();
+    assert_eq!(names, vec!["new.example.com"]);
+}
+
+#[tokio::test]
+async fn reconcile_keeps_unset_fields_in_a_mixed_zone() {
+    let fake = FakeCloudflare::start().await;
+    let zone_id = fake.add_zone("example.com");
+    fake.add_record(
+        &zone_id,
+        ARecordRequest {
+            ttl: Some(3600),
+            comment: Some("edge".to_string()),
+            ..a("www.example.com", [192, 0, 2, 1])
+        },
+    );
+    let ns: RecordRequest = serde_json::from_value(serde_json::json!({
+        "type": "NS",
+        "name": "sub.example.com",
+        "content": "ns1.example.net"
+    }))
+    .unwrap();
+    fake.add_record(&zone_id, ns);
+    let srv: RecordRequest = serde_json::from_value(serde_json::json!({
+        "type": "SRV",
+        "name": "_sip._tcp.example.com",
+        "content": "5 5060 sip.example.com",
+        "priority": 10,
+        "data": {"priority": 10, "weight": 5, "port": 5060, "target": "sip.example.com"}
+    }))
+    .unwrap();
+    fake.add_record(&zone_id, srv);
+    let c = fake.client();
+
+    let records = c.dns().list_all_records(&zone_id).await.unwrap();
+    let mut types: Vec<&str> = records.iter().map(|r| r.record_type()).collect();
+    types.sort();
+    assert_eq!(types, vec!["A", "NS", "SRV"]);
+    let srv = records.iter().find(|r| r.record_type() == "SRV").unwrap();
+    match srv {
+        RecordMessage::Other(r) => assert_eq!(r.extra["data"]["port"], 5060),
+        r => panic!("unexpected record {:?}", r),
+    }
+
+    let reconciler = c
+        .dns()
+        .reconciler(zone_id.clone())
+        .with_ownership(Ownership::All);
+    let desired: Vec<RecordRequest> = records
+        .iter()
+        .filter(|r| r.record_type() != "A")
+        .cloned()
+        .map(RecordRequest::from)
+        .chain([RecordRequest::from(a("www.example.com", [192, 0, 2, 2]))])
+        .collect();
+    let plan = reconciler.plan(desired).await.unwrap();
+    assert_eq!(plan.changes.len(), 1, "{}", plan);
+    reconciler.apply(&plan).await.into_result().unwrap();
+
+    let www = fake
+        .records(&zone_id)
+        .into_iter()
+        .find_map(|r| match r {
+            RecordMessage::A(r) => Some(r),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(www.content, Some(Ipv4Addr::new(192, 0, 2, 2)));
+    assert_eq!(www.ttl, Some(3600));
+    assert_eq!(www.comment.as_deref(), Some("edge"));
+}
+
+#[tokio::test]
+async fn reconciles_and_updates_ddns() {
+    let fake = FakeCloudflare::start().await;
+    let zone_id = fake.add_zone("example.com");
+    fake.add_record(&zone_id, txt("stale.example.com", "x"));
+    let c = fake.client();
+
+    let reconciler = c.dns().reconciler(zone_id.clone());
+    let plan = reconciler
+        .plan(vec![
+            RecordRequest::from(a("www.example.com", [192, 0, 2, 1])),
+            RecordRequest::from(txt("example.com", "v=spf1 -all")),
+        ])
+        .await
+        .unwrap();
+    reconciler.apply(&plan).await.into_result().unwrap();
+    assert_eq!(fake.records(&zone_id).len(), 2);
+    let plan = reconciler
+        .plan(vec![
+            RecordRequest::from(a("www.example.com", [192, 0, 2, 1])),
+            RecordRequest::from(txt("example.com", "v=spf1 -all")),
+        ])
+        .await
+        .unwrap();
+    assert!(plan.is_empty(), "{}", plan);
+
+    let updater = c
+        .dns()
+        .ddns(zone_id.clone(), "home.example.com")
+        .with_families(&[IpFamily::V4])
+        .with_create_missing(true)
+        .with_source(FixedIpSource {
+            v4: Some(Ipv4Addr::new(198, 51, 100, 7)),
+            v6: None,
+        });
+    let status = updater.update_once().await.into_result().unwrap();
+    assert!(matches!(status[0].1, DdnsStatus::Created(_)));
+    let status = updater.update_once().await.into_result().unwrap();
+    assert!(matches!(status[0].1, DdnsStatus::Unchanged(_)));
+
+    let moved = c
+        .dns()
+        .ddns(zone_id.clone(), "home.example.com")
+        .with_families(&[IpFamily::V4, IpFamily::V6])
+        .with_source(FixedIpSource {
+            v4: Some(Ipv4Addr::new(198, 51, 100, 8)),
+            v6: None,
+        });
+    let report = moved.update_once().await;
+    assert!(!report.is_success());
+    assert_eq!(report.results.len(), 2);
+    assert_eq!(
+        report.results[0].1.as_ref().unwrap(),
+        &DdnsStatus::Updated {
+            from: Some(Ipv4Addr::new(198, 51, 100, 7).into()),
+            to: Ipv4Addr::new(198, 51, 100, 8).into(),
+        }
+    );
+    assert_eq!(report.results[1].0, IpFamily::V6);
+    assert!(report.results[1].1.is_err());
+    let home: Vec<_> = fake
+        .records(&zone_id)
+        .into_iter()
+        .filter_map(|r| match r {
+            RecordMessage::A(r) if r.name.as_deref() == Some("home.example.com") => r.content,
+            _ => None,
+        })
+        .collect();
+    assert_eq!(home, vec![Ipv4Addr::new(198, 51, 100, 8)]);
+
+    let zones = c
+        .zones()
+        .list_zones(ListZonesRequest {
+            name: Some("example.com".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert_eq!(zones.len(), 1);
+    let export = c.dns().export_records(&zone_id).await.unwrap();
+    assert!(export.contains("home.example.com."), "{}", export);
+}
+
+#[tokio::test]
+async fn filters_by_tags_and_comments() {
+    let fake = FakeCloudflare::start().await;
+    let zone_id = fake.add_zone("example.com");
+    let tagged = |name: &str, tags: &[&str], comment: Option<&str>| TXTRecordRequest {
+        tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+        comment: comment.map(str::to_string),
+        ..txt(name, "v")
+    };
+    fake.add_record(
+        &zone_id,
+        tagged("a", &["owner:team-x"], Some("managed by x")),
+    );
+    fake.add_record(&zone_id, tagged("b", &["owner:team-y", "env:prod"], None));
+    fake.add_record(&zone_id, tagged("c", &[], Some("hand edited")));
+    let dns = fake.client().dns();
+    let names = |records: Vec<RecordMessage>| -> Vec<String> {
+        let mut names: Vec<String> = records
+            .into_iter()
+            .filter_map(|r| RecordRequest::from(r).name().map(str::to_string))
+            .collect();
+        names.sort();
+        names
+    };
+
+    let owned = dns
+        .records_owned_by(&zone_id, "owner:team-x")
+        .await
+        .unwrap();
+    assert_eq!(names(owned), vec!["a.example.com"]);
+    let owned = dns.records_owned_by(&zone_id, "owner").await.unwrap();
+    assert_eq!(names(owned), vec!["a.example.com", "b.example.com"]);
+
+    let filter = RecordFilter::new()
+        .with_tag(TagFilter::Absent("owner".to_string()))
+        .with_comment(TextMatch::StartsWith("hand".to_string()));
+    let found = dns
+        .list_all_records_matching(&zone_id, &filter)
+        .await
+        .unwrap();
+    assert_eq!(names(found), vec!["c.example.com"]);
+
+    let filter = RecordFilter::new()
+        .with_tag(TagFilter::Contains("owner".to_string(), "-y".to_string()))
+        .with_comment_present(true)
+        .with_match(MatchMode::Any);
+    let found = dns
+        .list_all_records_matching(&zone_id, &filter)
+        .await
+        .unwrap();
+    assert_eq!(
+        names(found),
+        vec!["a.example.com", "b.example.com", "c.example.com"]
+    );
+
+    let filter = RecordFilter::new()
+        .with_tag(TagFilter::from_tag("owner:team-x"))
+        .with_tag(TagFilter::from_tag("env:prod"))
+        .with_tag_match(MatchMode::Any);
+    let found = dns
+        .list_all_records_matching(&zone_id, &filter)
+        .await
+        .unwrap();
+    assert_eq!(names(found), vec!["a.example.com", "b.example.com"]);
+    assert!(fake
+        .requests()
+        .iter()
+        .any(|r| r.contains("tag.exact=env%3Aprod&tag.exact=owner%3Ateam-x")));
+}